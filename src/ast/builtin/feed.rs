@@ -0,0 +1,63 @@
+use crate::ast::environment::{convert, Scope, Value};
+use crate::ast::statement::Statement;
+use crate::context::{Context, Feed};
+use crate::error::{DaisyError, DaisyResult};
+use crate::output::feed;
+
+// writes a feed for a list of page metas, like the ones from `std.pages`, and returns its url
+pub fn builtin_feed(
+    ctx: &mut Context,
+    _: &Vec<Statement>,
+    inputs: &Vec<Value>,
+    scope: &mut Scope,
+) -> DaisyResult<Value> {
+    if inputs.is_empty() || inputs.len() > 2 {
+        return Err(DaisyError::new(format!(
+            "Expected 1 or 2 arguments for 'feed', got {}",
+            inputs.len()
+        )));
+    }
+
+    let mut pages = vec![];
+    match &inputs[0] {
+        Value::Array(array) => {
+            let mut array = array.clone();
+            for index in array.get_indices() {
+                match array.get(&index) {
+                    Some(Value::Map(meta)) => pages.push(meta.clone()),
+                    Some(value) => {
+                        return Err(DaisyError::new(format!(
+                            "Expected a list of page metas for 'feed', got {} in it",
+                            value.get_type()
+                        )));
+                    }
+                    None => {}
+                }
+            }
+        }
+        value => {
+            return Err(DaisyError::new(format!(
+                "Expected a list of page metas for 'feed', got {}",
+                value.get_type()
+            )));
+        }
+    }
+
+    // the options are the same as a [[feed]] table in the config
+    let options = match inputs.get(1) {
+        Some(options @ Value::Map(_)) => {
+            let options = convert::to_json(ctx, options, scope)?;
+            serde_json::from_value(options)
+                .map_err(|err| DaisyError::new(format!("Invalid feed options: {}", err)))?
+        }
+        Some(value) => {
+            return Err(DaisyError::new(format!(
+                "Expected a map of options for 'feed', got {}",
+                value.get_type()
+            )));
+        }
+        None => Feed::default(),
+    };
+
+    Ok(Value::String(feed::write_feed(ctx, &options, pages)?))
+}
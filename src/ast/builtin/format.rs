@@ -1,18 +1,19 @@
 use crate::ast::environment::{Scope, Value};
 use crate::ast::statement::Statement;
 use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
 
 pub fn builtin_format(
     ctx: &mut Context,
     _: &Vec<Statement>,
     inputs: &Vec<Value>,
     scope: &mut Scope,
-) -> Value {
+) -> DaisyResult<Value> {
     if inputs.len() == 0 {
-        panic!(
+        return Err(DaisyError::new(format!(
             "Expected atleast one argument for 'format', got {}",
             inputs.len()
-        );
+        )));
     }
 
     if let Value::String(src) = &inputs[0] {
@@ -27,20 +28,25 @@ pub fn builtin_format(
                     let keys = map.get_keys();
                     for key in keys {
                         if let Some(val) = map.get(&key) {
-                            src = src.replace(&format!("{{{}}}", key), &val.render(ctx, scope));
+                            src = src.replace(&format!("{{{}}}", key), &val.render(ctx, scope)?);
                         } else {
-                            panic!("Key '{}' not found in map", key);
+                            return Err(DaisyError::new(format!("Key '{}' not found in map", key)));
                         }
                     }
                 }
-                _ => panic!("Unsupported type for 'format': {}", value.get_type()),
+                _ => {
+                    return Err(DaisyError::new(format!(
+                        "Unsupported type for 'format': {}",
+                        value.get_type()
+                    )))
+                }
             }
         }
-        Value::String(src)
+        Ok(Value::String(src))
     } else {
-        panic!(
+        Err(DaisyError::new(format!(
             "Expected a string argument for 'format', got {}",
             inputs[0].get_type()
-        )
+        )))
     }
 }
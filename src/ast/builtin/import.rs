@@ -2,6 +2,7 @@ use crate::ast::environment::{Scope, Value};
 use crate::ast::function::default_function;
 use crate::ast::statement::Statement;
 use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
 use crate::resolver::{self, resource::Resource};
 
 pub fn builtin_use(
@@ -9,42 +10,44 @@ pub fn builtin_use(
     _: &Vec<Statement>,
     inputs: &Vec<Value>,
     env: &mut Scope,
-) -> Value {
+) -> DaisyResult<Value> {
     if inputs.len() == 0 {
-        panic!(
+        return Err(DaisyError::new(format!(
             "Expected atleast one argument for 'use', got {}",
             inputs.len()
-        );
+        )));
     }
 
     if let Value::String(import) = &inputs[0] {
-        let resource = resolver::get_file(ctx, import.clone());
-        match &*resource.unwrap().borrow() {
+        let resource = resolver::get_file(ctx, import.clone())?;
+        let value = match &*resource.borrow() {
             Resource::File(file) => {
                 let meta = env.get_meta();
                 let mut scope = Scope::new();
-                super::init(&mut scope);
+                super::init(ctx, &mut scope)?;
                 if let Some(meta) = meta {
-                    scope.set_meta(meta.clone());
+                    scope.set_meta(meta.clone())?;
                 } else {
-                    panic!("No meta found in environment for 'use' statement");
+                    return Err(DaisyError::new(
+                        "No meta found in environment for 'use' statement",
+                    ));
                 }
-                default_function(ctx, &file.ast, &vec![], &mut scope)
+                ctx.dependencies.enter(&file.src);
+                let value = default_function(ctx, &file.ast, &vec![], &mut scope);
+                ctx.dependencies.leave();
+                value.map_err(|err| err.with_file(&file.src))?
             }
-            Resource::SCSS(_, path, _) => {
-                let relative_path = Resource::get_relative_path(ctx, path).unwrap().to_string();
-                Value::String(relative_path)
-            }
-            Resource::Other(_, output) => Resource::get_relative_path(ctx, output)
-                .map(|path| Value::String(path))
-                .unwrap_or_else(|_| {
-                    panic!("Failed to get relative path for resource: {}", import);
-                }),
-        }
+            Resource::Markdown(markdown) => Value::raw(markdown.html.clone()),
+            Resource::Data(_, value) => value.clone(),
+            Resource::Generated(output, _) => Value::String(Resource::get_url(ctx, output)?),
+            Resource::SCSS(_, path, _) => Value::String(Resource::get_url(ctx, path)?),
+            Resource::Other(_, output) => Value::String(Resource::get_url(ctx, output)?),
+        };
+        Ok(value)
     } else {
-        panic!(
+        Err(DaisyError::new(format!(
             "Expected a string argument for 'use', got {}",
             inputs[0].get_type()
-        )
+        )))
     }
 }
@@ -0,0 +1,63 @@
+use crate::ast::environment::{convert, Scope, Value};
+use crate::ast::statement::Statement;
+use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
+
+pub fn builtin_parse(
+    _: &mut Context,
+    _: &Vec<Statement>,
+    inputs: &Vec<Value>,
+    _: &mut Scope,
+) -> DaisyResult<Value> {
+    if inputs.len() != 1 {
+        return Err(DaisyError::new(format!(
+            "Expected 1 argument for 'json.parse', got {}",
+            inputs.len()
+        )));
+    }
+
+    if let Value::String(json) = &inputs[0] {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .map_err(|err| DaisyError::new(format!("Invalid JSON: {}", err)))?;
+        Ok(convert::from_json(&value))
+    } else {
+        Err(DaisyError::new(format!(
+            "Expected a string argument for 'json.parse', got {}",
+            inputs[0].get_type()
+        )))
+    }
+}
+
+pub fn builtin_stringify(
+    ctx: &mut Context,
+    _: &Vec<Statement>,
+    inputs: &Vec<Value>,
+    scope: &mut Scope,
+) -> DaisyResult<Value> {
+    if inputs.is_empty() || inputs.len() > 2 {
+        return Err(DaisyError::new(format!(
+            "Expected 1 or 2 arguments for 'json.stringify', got {}",
+            inputs.len()
+        )));
+    }
+
+    let pretty = match inputs.get(1) {
+        Some(Value::Bool(pretty)) => *pretty,
+        Some(value) => {
+            return Err(DaisyError::new(format!(
+                "Expected a bool for the second argument of 'json.stringify', got {}",
+                value.get_type()
+            )));
+        }
+        None => false,
+    };
+
+    let json = convert::to_json(ctx, &inputs[0], scope)?;
+    let json = if pretty {
+        serde_json::to_string_pretty(&json)
+    } else {
+        serde_json::to_string(&json)
+    };
+    json.map(Value::String)
+        .map_err(|err| DaisyError::new(format!("Failed to serialize JSON: {}", err)))
+}
@@ -0,0 +1,22 @@
+use crate::ast::environment::{Scope, Value};
+use crate::ast::statement::Statement;
+use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
+use crate::resolver::markdown;
+
+pub fn builtin_markdown(
+    ctx: &mut Context,
+    _: &Vec<Statement>,
+    inputs: &Vec<Value>,
+    scope: &mut Scope,
+) -> DaisyResult<Value> {
+    if inputs.len() != 1 {
+        return Err(DaisyError::new(format!(
+            "Expected 1 argument for 'markdown', got {}",
+            inputs.len()
+        )));
+    }
+
+    let source = inputs[0].render(ctx, scope)?;
+    Ok(Value::raw(markdown::render(&source)))
+}
@@ -1,21 +1,54 @@
-use super::environment::{Scope, Type, Value};
-use import::builtin_use;
+use super::environment::{convert, Scope, Type, Value};
+use crate::context::Context;
+use crate::error::DaisyResult;
+use crate::resolver::data;
+pub use import::builtin_use;
 
+mod feed;
 mod format;
 mod import;
+mod json;
+mod markdown;
 mod pages;
 mod print;
+mod raw;
 mod replace;
+mod url;
 
-pub fn init(scope: &mut Scope) {
+pub fn init(ctx: &mut Context, scope: &mut Scope) -> DaisyResult<()> {
     let mut builtin = Scope::new();
 
+    builtin.define_builtin_function("feed".into(), feed::builtin_feed, Type::String);
     builtin.define_builtin_function("format".into(), format::builtin_format, Type::String);
     builtin.define_builtin_function("replace".into(), replace::builtin_replace, Type::String);
+    builtin.define_builtin_function("markdown".into(), markdown::builtin_markdown, Type::Element);
     builtin.define_builtin_function("pages".into(), pages::builtin_pages, Type::Any);
     builtin.define_builtin_function("print".into(), print::builtin_print, Type::Any);
     builtin.define_builtin_function("println".into(), print::builtin_println, Type::Any);
+    builtin.define_builtin_function("raw".into(), raw::builtin_raw, Type::Element);
 
-    scope.define(Type::Map, "std".into(), Value::Map(builtin));
+    let mut json = Scope::new();
+    json.define_builtin_function("parse".into(), json::builtin_parse, Type::Any);
+    json.define_builtin_function("stringify".into(), json::builtin_stringify, Type::String);
+    builtin.define(Type::Map, "json".into(), Value::Map(json))?;
+
+    let mut url = Scope::new();
+    url.define_builtin_function("absolute".into(), url::builtin_absolute, Type::String);
+    url.define_builtin_function("relative".into(), url::builtin_relative, Type::String);
+    builtin.define(Type::Map, "url".into(), Value::Map(url))?;
+
+    scope.define(Type::Map, "std".into(), Value::Map(builtin))?;
+    scope.define_constant(
+        Type::Map,
+        "config".into(),
+        convert::from_toml_table(&ctx.config.table),
+    )?;
+    scope.define_constant(
+        Type::Map,
+        "site".into(),
+        convert::from_toml_table(&ctx.config.site),
+    )?;
+    scope.define_constant(Type::Map, "data".into(), data::get_data(ctx)?)?;
     scope.define_builtin_function("use".into(), builtin_use, Type::Any);
+    Ok(())
 }
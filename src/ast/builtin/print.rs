@@ -1,18 +1,19 @@
 use crate::ast::environment::{Scope, Value};
 use crate::ast::statement::Statement;
 use crate::context::Context;
+use crate::error::DaisyResult;
 
 pub fn builtin_print(
     ctx: &mut Context,
     _: &Vec<Statement>,
     inputs: &Vec<Value>,
     scope: &mut Scope,
-) -> Value {
+) -> DaisyResult<Value> {
     for input in inputs.iter() {
-        let rendered = input.render(ctx, scope);
+        let rendered = input.render(ctx, scope)?;
         print!("{}", rendered);
     }
-    Value::Nil
+    Ok(Value::Nil)
 }
 
 pub fn builtin_println(
@@ -20,10 +21,10 @@ pub fn builtin_println(
     _: &Vec<Statement>,
     inputs: &Vec<Value>,
     scope: &mut Scope,
-) -> Value {
+) -> DaisyResult<Value> {
     for input in inputs.iter() {
-        let rendered = input.render(ctx, scope);
+        let rendered = input.render(ctx, scope)?;
         println!("{}", rendered);
     }
-    Value::Nil
+    Ok(Value::Nil)
 }
@@ -0,0 +1,22 @@
+use crate::ast::environment::{Scope, Value};
+use crate::ast::statement::Statement;
+use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
+
+// marks trusted html so the rendered value is inserted without escaping
+pub fn builtin_raw(
+    ctx: &mut Context,
+    _: &Vec<Statement>,
+    inputs: &Vec<Value>,
+    scope: &mut Scope,
+) -> DaisyResult<Value> {
+    if inputs.len() != 1 {
+        return Err(DaisyError::new(format!(
+            "Expected 1 argument for 'raw', got {}",
+            inputs.len()
+        )));
+    }
+
+    let html = inputs[0].render(ctx, scope)?;
+    Ok(Value::raw(html))
+}
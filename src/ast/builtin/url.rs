@@ -0,0 +1,79 @@
+use crate::ast::environment::{Scope, Value};
+use crate::ast::statement::Statement;
+use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
+
+pub fn builtin_absolute(
+    ctx: &mut Context,
+    _: &Vec<Statement>,
+    inputs: &Vec<Value>,
+    _: &mut Scope,
+) -> DaisyResult<Value> {
+    if inputs.len() != 1 {
+        return Err(DaisyError::new(format!(
+            "Expected 1 argument for 'url.absolute', got {}",
+            inputs.len()
+        )));
+    }
+
+    if let Value::String(path) = &inputs[0] {
+        let path = ctx.strip_base_path(path).to_string();
+        Ok(Value::String(ctx.get_absolute_url(&path)))
+    } else {
+        Err(DaisyError::new(format!(
+            "Expected a string argument for 'url.absolute', got {}",
+            inputs[0].get_type()
+        )))
+    }
+}
+
+pub fn builtin_relative(
+    _: &mut Context,
+    _: &Vec<Statement>,
+    inputs: &Vec<Value>,
+    _: &mut Scope,
+) -> DaisyResult<Value> {
+    if inputs.len() != 2 {
+        return Err(DaisyError::new(format!(
+            "Expected 2 arguments for 'url.relative', got {}",
+            inputs.len()
+        )));
+    }
+
+    match (&inputs[0], &inputs[1]) {
+        (Value::String(from), Value::String(to)) => Ok(Value::String(relative(from, to))),
+        (from, to) => Err(DaisyError::new(format!(
+            "Expected two string arguments for 'url.relative', got {} and {}",
+            from.get_type(),
+            to.get_type()
+        ))),
+    }
+}
+
+// the path that leads from the page at `from` to `to`, both are urls on the same site
+fn relative(from: &str, to: &str) -> String {
+    let directory = match from.rfind('/') {
+        Some(index) => &from[..index],
+        None => "",
+    };
+    let from: Vec<&str> = directory.split('/').filter(|s| !s.is_empty()).collect();
+    let to_segments: Vec<&str> = to.split('/').filter(|s| !s.is_empty()).collect();
+
+    let common = from
+        .iter()
+        .zip(&to_segments)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut segments = vec![".."; from.len() - common];
+    segments.extend(&to_segments[common..]);
+
+    let mut path = segments.join("/");
+    if path.is_empty() {
+        path.push('.');
+    }
+    if to.ends_with('/') {
+        path.push('/');
+    }
+    path
+}
@@ -0,0 +1,178 @@
+use super::{Scope, Value};
+use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
+
+pub fn from_toml(value: &toml::Value) -> Value {
+    match value {
+        toml::Value::String(string) => Value::String(string.clone()),
+        toml::Value::Integer(number) => Value::Number(*number),
+        toml::Value::Float(number) => Value::Float(*number),
+        toml::Value::Boolean(boolean) => Value::Bool(*boolean),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(values) => {
+            let mut array = Scope::new();
+            for value in values {
+                array.array_push(from_toml(value));
+            }
+            Value::Array(array)
+        }
+        toml::Value::Table(table) => from_toml_table(table),
+    }
+}
+
+pub fn from_toml_table(table: &toml::Table) -> Value {
+    let mut map = Scope::new();
+    for (key, value) in table {
+        map.map_insert(key.clone(), from_toml(value));
+    }
+    Value::Map(map)
+}
+
+pub fn from_yaml(value: &serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Nil,
+        serde_yaml::Value::Bool(boolean) => Value::Bool(*boolean),
+        serde_yaml::Value::Number(number) => match number.as_i64() {
+            Some(number) => Value::Number(number),
+            None => Value::Float(number.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_yaml::Value::String(string) => Value::String(string.clone()),
+        serde_yaml::Value::Sequence(values) => {
+            let mut array = Scope::new();
+            for value in values {
+                array.array_push(from_yaml(value));
+            }
+            Value::Array(array)
+        }
+        serde_yaml::Value::Mapping(mapping) => {
+            let mut map = Scope::new();
+            for (key, value) in mapping {
+                let key = match key {
+                    serde_yaml::Value::String(key) => key.clone(),
+                    key => serde_yaml::to_string(key)
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                };
+                map.map_insert(key, from_yaml(value));
+            }
+            Value::Map(map)
+        }
+        serde_yaml::Value::Tagged(tagged) => from_yaml(&tagged.value),
+    }
+}
+
+pub fn from_json(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Nil,
+        serde_json::Value::Bool(boolean) => Value::Bool(*boolean),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(number) => Value::Number(number),
+            None => Value::Float(number.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_json::Value::String(string) => Value::String(string.clone()),
+        serde_json::Value::Array(values) => {
+            let mut array = Scope::new();
+            for value in values {
+                array.array_push(from_json(value));
+            }
+            Value::Array(array)
+        }
+        serde_json::Value::Object(object) => {
+            let mut map = Scope::new();
+            for (key, value) in object {
+                map.map_insert(key.clone(), from_json(value));
+            }
+            Value::Map(map)
+        }
+    }
+}
+
+// every record becomes a map from the column names in the header to the field, fields are strings
+pub fn from_csv(headers: &csv::StringRecord, records: &[csv::StringRecord]) -> Value {
+    let mut array = Scope::new();
+    for record in records {
+        let mut map = Scope::new();
+        for (header, field) in headers.iter().zip(record.iter()) {
+            map.map_insert(header.to_string(), Value::String(field.to_string()));
+        }
+        array.array_push(Value::Map(map));
+    }
+    Value::Array(array)
+}
+
+// elements are written as the html they render to, functions have no json representation
+pub fn to_json(
+    ctx: &mut Context,
+    value: &Value,
+    scope: &mut Scope,
+) -> DaisyResult<serde_json::Value> {
+    let json = match value {
+        Value::String(string) => serde_json::Value::String(string.clone()),
+        Value::Number(number) => serde_json::Value::from(*number),
+        Value::Float(number) => serde_json::Number::from_f64(*number)
+            .map_or(serde_json::Value::Null, serde_json::Value::Number),
+        Value::Bool(boolean) => serde_json::Value::Bool(*boolean),
+        Value::Element(node) => serde_json::Value::String(node(ctx, scope)?),
+        Value::Function(..) => {
+            return Err(DaisyError::new("Functions can't be converted to JSON"));
+        }
+        Value::Map(map) => {
+            let mut map = map.clone();
+            let mut object = serde_json::Map::new();
+            for key in map.get_keys() {
+                if let Some(value) = map.get(&key).cloned() {
+                    object.insert(key, to_json(ctx, &value, &mut map)?);
+                }
+            }
+            serde_json::Value::Object(object)
+        }
+        Value::Array(array) => {
+            let mut array = array.clone();
+            let mut values = vec![];
+            for key in array.get_indices() {
+                if let Some(value) = array.get(&key).cloned() {
+                    values.push(to_json(ctx, &value, &mut array)?);
+                }
+            }
+            serde_json::Value::Array(values)
+        }
+        Value::Nil => serde_json::Value::Null,
+        Value::Scoped(scope, value) => to_json(ctx, value, &mut scope.clone())?,
+    };
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{from_json, to_json};
+    use crate::ast::environment::Scope;
+    use crate::context::{Config, Context};
+
+    #[test]
+    fn json_round_trips() {
+        let mut ctx = Context::new(Config::default());
+        let json = json!({
+            "title": "Hello",
+            "count": 3,
+            "ratio": 0.5,
+            "draft": false,
+            "tags": ["a", "b", null],
+            "author": { "name": "Ann" },
+        });
+
+        let value = from_json(&json);
+        assert_eq!(to_json(&mut ctx, &value, &mut Scope::new()).unwrap(), json);
+    }
+
+    #[test]
+    fn functions_have_no_json() {
+        let mut ctx = Context::new(Config::default());
+        let mut scope = Scope::new();
+        crate::ast::builtin::init(&mut ctx, &mut scope).unwrap();
+        let std = scope.get("std").cloned().unwrap();
+        assert!(to_json(&mut ctx, &std, &mut scope).is_err());
+    }
+}
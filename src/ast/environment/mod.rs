@@ -1,3 +1,4 @@
+pub mod convert;
 pub mod scope;
 pub mod r#type;
 pub mod value;
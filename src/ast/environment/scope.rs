@@ -1,14 +1,34 @@
 use super::{Type, Value};
 use crate::ast::statement::Statement;
 use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
 use std::collections::HashMap;
 
 #[derive(Clone)]
 pub struct Scope {
-    variables: Vec<HashMap<String, (Type, Value)>>,
+    variables: Vec<HashMap<String, Variable>>,
     current_scope: usize,
 }
 
+// read-only variables can't be assigned to, but a definition in the same or an inner scope
+// shadows them
+#[derive(Clone)]
+struct Variable {
+    type_: Type,
+    value: Value,
+    read_only: bool,
+}
+
+impl Variable {
+    fn new(type_: Type, value: Value) -> Self {
+        Variable {
+            type_,
+            value,
+            read_only: false,
+        }
+    }
+}
+
 impl Scope {
     pub fn new() -> Self {
         Self {
@@ -20,8 +40,8 @@ impl Scope {
     #[allow(dead_code)]
     pub fn print_current_scope(&self) {
         println!("Current scope: {}", self.current_scope);
-        for (name, value) in &self.variables[self.current_scope] {
-            println!("{}: {} ({})", name, value.1, value.0);
+        for (name, variable) in &self.variables[self.current_scope] {
+            println!("{}: {} ({})", name, variable.value, variable.type_);
         }
     }
 
@@ -40,7 +60,19 @@ impl Scope {
 
     pub fn array_push(&mut self, value: Value) {
         let index = self.get_indices().len();
-        self.define(Type::Any, index.to_string(), value);
+        self.variables[self.current_scope]
+            .insert(index.to_string(), Variable::new(Type::Any, value));
+    }
+
+    pub fn map_insert(&mut self, name: String, value: Value) {
+        self.variables[self.current_scope].insert(name, Variable::new(value.get_type(), value));
+    }
+
+    // copies all entries of `other` into this scope, existing keys are replaced
+    pub fn merge(&mut self, other: &Scope) {
+        for (name, entry) in &other.variables[other.current_scope] {
+            self.variables[self.current_scope].insert(name.clone(), entry.clone());
+        }
     }
 
     pub fn sync_scope(&mut self) {
@@ -70,42 +102,64 @@ impl Scope {
     pub fn define_builtin_function(
         &mut self,
         name: String,
-        func: fn(&mut Context, &Vec<Statement>, &Vec<Value>, &mut Scope) -> Value,
+        func: fn(&mut Context, &Vec<Statement>, &Vec<Value>, &mut Scope) -> DaisyResult<Value>,
         return_type: Type,
     ) {
-        self.define(
-            Type::Function,
+        self.variables[self.current_scope].insert(
             name,
-            Value::Function(func, vec![].into(), return_type, vec![].into()),
+            Variable::new(
+                Type::Function,
+                Value::Function(func, vec![].into(), return_type, vec![].into()),
+            ),
         );
     }
 
-    pub fn define(&mut self, type_: Type, name: String, value: Value) {
-        if self.variables[self.current_scope].contains_key(&name) {
-            panic!("Value {} already defined in this scope", name);
+    pub fn define(&mut self, type_: Type, name: String, value: Value) -> DaisyResult<()> {
+        let defined = self.variables[self.current_scope].get(&name);
+        if defined.is_some_and(|variable| !variable.read_only) {
+            return Err(DaisyError::new(format!(
+                "Value {} already defined in this scope",
+                name
+            )));
         }
         if !Type::matches(&type_, &value) {
-            panic!("Type mismatch: expected {}, got {}", type_, value);
+            return Err(DaisyError::new(format!(
+                "Type mismatch for {}: expected {}, got {}",
+                name, type_, value
+            )));
+        }
+        self.variables[self.current_scope].insert(name, Variable::new(type_, value));
+        Ok(())
+    }
+
+    pub fn define_constant(&mut self, type_: Type, name: String, value: Value) -> DaisyResult<()> {
+        self.define(type_, name.clone(), value)?;
+        if let Some(variable) = self.variables[self.current_scope].get_mut(&name) {
+            variable.read_only = true;
         }
-        self.variables[self.current_scope].insert(name, (type_, value));
+        Ok(())
     }
 
-    pub fn set_meta(&mut self, value: Value) {
-        self.overwrite(Type::Map, "meta".into(), value);
+    pub fn set_meta(&mut self, value: Value) -> DaisyResult<()> {
+        self.overwrite(Type::Map, "meta".into(), value)
     }
 
     pub fn get_meta(&self) -> Option<&Value> {
         self.get_from_scope("meta", self.current_scope)
     }
 
-    pub fn overwrite(&mut self, type_: Type, name: String, value: Value) {
+    pub fn overwrite(&mut self, type_: Type, name: String, value: Value) -> DaisyResult<()> {
         if self.variables[self.current_scope].contains_key(&name) {
             self.variables[self.current_scope].remove(&name);
         }
         if !Type::matches(&type_, &value) {
-            panic!("Type mismatch: expected {}, got {}", type_, value);
+            return Err(DaisyError::new(format!(
+                "Type mismatch for {}: expected {}, got {}",
+                name, type_, value
+            )));
         }
-        self.variables[self.current_scope].insert(name, (type_, value));
+        self.variables[self.current_scope].insert(name, Variable::new(type_, value));
+        Ok(())
     }
 
     pub fn get(&self, name: &str) -> Option<&Value> {
@@ -113,8 +167,8 @@ impl Scope {
     }
 
     fn get_from_scope(&self, name: &str, scope: usize) -> Option<&Value> {
-        if let Some((_, var)) = self.variables[scope].get(name) {
-            return Some(var);
+        if let Some(variable) = self.variables[scope].get(name) {
+            return Some(&variable.value);
         }
         if scope > 0 {
             return self.get_from_scope(name, scope - 1);
@@ -122,20 +176,105 @@ impl Scope {
         None
     }
 
-    pub fn set(&mut self, name: String, value: Value) {
-        self.set_in_scope(name, value, self.current_scope);
+    pub fn is_read_only(&self, name: &str) -> bool {
+        (0..=self.current_scope)
+            .rev()
+            .find_map(|scope| self.variables[scope].get(name))
+            .is_some_and(|variable| variable.read_only)
     }
 
-    fn set_in_scope(&mut self, name: String, value: Value, scope: usize) {
-        if let Some((type_, var)) = self.variables[self.current_scope].get_mut(&name) {
-            if !Type::matches(type_, &value) {
-                panic!("Type mismatch: expected {}, got {}", type_, value);
+    pub fn set(&mut self, name: String, value: Value) -> DaisyResult<()> {
+        self.set_in_scope(name, value, self.current_scope)
+    }
+
+    fn set_in_scope(&mut self, name: String, value: Value, scope: usize) -> DaisyResult<()> {
+        if let Some(variable) = self.variables[scope].get_mut(&name) {
+            if variable.read_only {
+                return Err(DaisyError::new(format!("Value {} is read-only", name)));
             }
-            var.set_value(value);
+            if !Type::matches(&variable.type_, &value) {
+                return Err(DaisyError::new(format!(
+                    "Type mismatch for {}: expected {}, got {}",
+                    name, variable.type_, value
+                )));
+            }
+            variable.value.set_value(value);
+            Ok(())
         } else if scope > 0 {
-            return self.set_in_scope(name, value, scope - 1);
+            self.set_in_scope(name, value, scope - 1)
         } else {
-            panic!("Value {} not found in any scope", name);
+            Err(DaisyError::new(format!(
+                "Value {} not found in any scope",
+                name
+            )))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Scope;
+    use crate::ast::environment::{Type, Value};
+    use crate::ast::test::run;
+
+    #[test]
+    fn constants_are_read_only() {
+        let mut scope = Scope::new();
+        scope
+            .define_constant(Type::String, "site".into(), Value::String("a".into()))
+            .unwrap();
+        let err = scope
+            .set("site".into(), Value::String("b".into()))
+            .unwrap_err();
+        assert_eq!(err.message, "Value site is read-only");
+    }
+
+    #[test]
+    fn constants_can_be_shadowed() {
+        let mut scope = Scope::new();
+        scope
+            .define_constant(Type::String, "site".into(), Value::String("a".into()))
+            .unwrap();
+        scope.wrap(|inner| {
+            inner
+                .define(Type::Number, "site".into(), Value::Number(1))
+                .unwrap();
+            inner.set("site".into(), Value::Number(2)).unwrap();
+            assert!(inner.get("site") == Some(&Value::Number(2)));
+        });
+        assert!(scope.get("site") == Some(&Value::String("a".into())));
+    }
+
+    #[test]
+    fn set_reaches_outer_scopes() {
+        let mut scope = Scope::new();
+        scope
+            .define(Type::Number, "count".into(), Value::Number(1))
+            .unwrap();
+        scope.wrap(|inner| inner.set("count".into(), Value::Number(2)).unwrap());
+        assert!(scope.get("count") == Some(&Value::Number(2)));
+    }
+
+    #[test]
+    fn parameters_shadow_builtin_constants() {
+        let value = run(r#"
+            func f = (str site) str {
+                return site
+            }
+            return f("mine")
+        "#)
+        .unwrap();
+        assert!(value == Value::String("mine".into()));
+    }
+
+    #[test]
+    fn page_variables_shadow_builtin_constants() {
+        let value = run(r#"
+            str config = "mine"
+            config = config + "!"
+            return config
+        "#)
+        .unwrap();
+        assert!(value == Value::String("mine!".into()));
+    }
+}
@@ -1,8 +1,10 @@
 use super::{Scope, Type};
 use crate::ast::expression::Expression;
+use crate::ast::html;
 use crate::ast::node::Node;
 use crate::ast::statement::Statement;
 use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
 use std::fmt::Display;
 use std::rc::Rc;
 
@@ -27,7 +29,7 @@ pub enum Value {
     Bool(bool),
     Element(Rc<Node>),
     Function(
-        fn(&mut Context, &Vec<Statement>, &Vec<Value>, &mut Scope) -> Value,
+        fn(&mut Context, &Vec<Statement>, &Vec<Value>, &mut Scope) -> DaisyResult<Value>,
         Rc<Vec<(Type, String, Option<Expression>)>>,
         Type,
         Rc<Vec<Statement>>,
@@ -40,13 +42,18 @@ pub enum Value {
 }
 
 impl Value {
-    pub fn render(&self, ctx: &mut Context, scope: &mut Scope) -> String {
-        match self {
+    // trusted html, wrapped in an element so it is inserted without escaping
+    pub fn raw(html: String) -> Value {
+        Value::Element(Rc::new(Box::new(move |_ctx, _scope| Ok(html.clone()))))
+    }
+
+    pub fn render(&self, ctx: &mut Context, scope: &mut Scope) -> DaisyResult<String> {
+        let output = match self {
             Value::String(s) => s.clone(),
             Value::Number(n) => n.to_string(),
             Value::Float(n) => n.to_string(),
             Value::Bool(b) => b.to_string(),
-            Value::Element(node) => node(ctx, scope),
+            Value::Element(node) => node(ctx, scope)?,
             Value::Function(..) => "".into(),
             Value::Map(scope) => {
                 let mut scope = scope.clone();
@@ -54,7 +61,7 @@ impl Value {
                 let mut output = String::new();
                 for key in keys {
                     if let Some(value) = scope.clone().get(&key) {
-                        let result = &value.render(ctx, &mut scope);
+                        let result = &value.render(ctx, &mut scope)?;
                         output.push_str(format!("{}; {}\n", key, result).as_str())
                     }
                 }
@@ -66,13 +73,38 @@ impl Value {
 
                 for key in scope.get_indices() {
                     if let Some(value) = scope.clone().get(&key) {
-                        output.push_str(&value.render(ctx, &mut scope));
+                        output.push_str(&value.render(ctx, &mut scope)?);
                     }
                 }
                 output
             }
             Value::Nil => "nil".to_string(),
-            Value::Scoped(scope, value) => value.render(ctx, &mut scope.clone()),
+            Value::Scoped(scope, value) => value.render(ctx, &mut scope.clone())?,
+        };
+        Ok(output)
+    }
+
+    // renders the value for use inside of html, elements are markup already and are inserted as
+    // is while every other value is escaped
+    pub fn render_html(&self, ctx: &mut Context, scope: &mut Scope) -> DaisyResult<String> {
+        match self {
+            Value::Element(node) => node(ctx, scope),
+            Value::Array(scope) => {
+                let mut scope = scope.clone();
+                let mut output = String::new();
+
+                for key in scope.get_indices() {
+                    if let Some(value) = scope.clone().get(&key) {
+                        output.push_str(&value.render_html(ctx, &mut scope)?);
+                    }
+                }
+                Ok(output)
+            }
+            Value::Scoped(scope, value) => value.render_html(ctx, &mut scope.clone()),
+            _ => {
+                let output = self.render(ctx, scope)?;
+                Ok(html::escape(ctx, &output))
+            }
         }
     }
 
@@ -96,10 +128,15 @@ impl Value {
     }
 
     #[allow(dead_code)]
-    pub fn assert_type(&self, type_: &Type) {
+    pub fn assert_type(&self, type_: &Type) -> DaisyResult<()> {
         if !Type::matches(type_, self) {
-            panic!("Type mismatch: expected {}, got {}", type_, self.get_type());
+            return Err(DaisyError::new(format!(
+                "Type mismatch: expected {}, got {}",
+                type_,
+                self.get_type()
+            )));
         }
+        Ok(())
     }
 
     impl_try_into!(try_into_string => String(s) -> String);
@@ -107,7 +144,7 @@ impl Value {
     impl_try_into!(try_into_float => Float(s) -> f64);
     impl_try_into!(try_into_bool => Bool(s) -> bool);
     impl_try_into!(try_into_element => Element(s) -> Rc<Node>);
-    impl_try_into!(try_into_function => Function(func, args, return_type, body) -> (fn(&mut Context, &Vec<Statement>, &Vec<Value>, &mut Scope) -> Value, Rc<Vec<(Type, String, Option<Expression>)>>, Type, Rc<Vec<Statement>>));
+    impl_try_into!(try_into_function => Function(func, args, return_type, body) -> (fn(&mut Context, &Vec<Statement>, &Vec<Value>, &mut Scope) -> DaisyResult<Value>, Rc<Vec<(Type, String, Option<Expression>)>>, Type, Rc<Vec<Statement>>));
     impl_try_into!(try_into_map => Map(scope) -> Scope);
     impl_try_into!(try_into_array => Array(scope) -> Scope);
     impl_try_into!(try_into_scoped => Scoped(scope, value) -> (Scope, Box<Value>));
@@ -119,6 +156,8 @@ impl PartialEq for Value {
             (Value::String(l), Value::String(r)) => l == r,
             (Value::Number(l), Value::Number(r)) => l == r,
             (Value::Float(l), Value::Float(r)) => l == r,
+            (Value::Number(l), Value::Float(r)) => (*l as f64) == *r,
+            (Value::Float(l), Value::Number(r)) => *l == (*r as f64),
             (Value::Bool(l), Value::Bool(r)) => l == r,
             (Value::Nil, Value::Nil) => true,
 
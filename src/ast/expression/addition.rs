@@ -1,21 +1,95 @@
-use crate::ast::environment::Value;
+use crate::ast::environment::{Scope, Value};
 
+use super::operands::{mismatch, operands, overflow, Operands};
 use super::Expression;
 
 pub fn addition(left: Box<Expression>, right: Box<Expression>) -> Expression {
     Box::new(move |ctx, scope| {
-        let left_value = left(ctx, scope);
-        let right_value = right(ctx, scope);
+        let left_value = left(ctx, scope)?;
+        let right_value = right(ctx, scope)?;
 
         match (&left_value, &right_value) {
-            (Value::Number(l), Value::Number(r)) => Value::Number(l + r),
-            (Value::Float(l), Value::Float(r)) => Value::Float(l + r),
-            (Value::String(l), Value::String(r)) => Value::String(format!("{}{}", l, r)),
-            _ => panic!(
-                "Type mismatch in addition: {} + {}",
-                left_value.get_type(),
-                right_value.get_type()
-            ),
+            (Value::String(_), _) | (_, Value::String(_)) => Ok(Value::String(format!(
+                "{}{}",
+                left_value.render(ctx, scope)?,
+                right_value.render(ctx, scope)?
+            ))),
+            (Value::Array(l), Value::Array(r)) => {
+                let mut l = l.clone();
+                let mut r = r.clone();
+                let mut array = Scope::new();
+                for array_scope in [&mut l, &mut r] {
+                    for index in array_scope.get_indices() {
+                        array.array_push(array_scope.get(&index).cloned().unwrap_or(Value::Nil));
+                    }
+                }
+                Ok(Value::Array(array))
+            }
+            (Value::Map(l), Value::Map(r)) => {
+                let mut map = l.clone();
+                map.merge(r);
+                Ok(Value::Map(map))
+            }
+            _ => match operands(&left_value, &right_value) {
+                Some(Operands::Number(l, r)) => l
+                    .checked_add(r)
+                    .map(Value::Number)
+                    .ok_or_else(|| overflow("addition", "+", l, r)),
+                Some(Operands::Float(l, r)) => Ok(Value::Float(l + r)),
+                None => Err(mismatch("addition", "+", &left_value, &right_value)),
+            },
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::environment::Value;
+    use crate::ast::test::run;
+
+    fn json(source: &str) -> Value {
+        run(&format!("return std.json.stringify({})", source)).unwrap()
+    }
+
+    #[test]
+    fn numbers_promote_to_floats() {
+        assert!(run("return 1 + 2").unwrap() == Value::Number(3));
+        assert!(run("return 1 + 0.5").unwrap() == Value::Float(1.5));
+        assert!(run("return 0.5 + 1").unwrap() == Value::Float(1.5));
+        assert!(run("return 0.25 + 0.25").unwrap() == Value::Float(0.5));
+    }
+
+    #[test]
+    fn strings_concatenate_rendered_values() {
+        assert!(run(r#"return "a" + "b""#).unwrap() == Value::String("ab".into()));
+        assert!(run(r#"return "n" + 1"#).unwrap() == Value::String("n1".into()));
+        assert!(run(r#"return 1.5 + "x""#).unwrap() == Value::String("1.5x".into()));
+        assert!(run(r#"return "is " + true"#).unwrap() == Value::String("is true".into()));
+    }
+
+    #[test]
+    fn lists_and_maps_concatenate() {
+        assert!(json("[1, 2] + [3]") == Value::String("[1,2,3]".into()));
+        assert!(
+            json(r#"{ num a = 1 num b = 2 } + { num b = 3 }"#)
+                == Value::String(r#"{"a":1,"b":3}"#.into())
+        );
+    }
+
+    #[test]
+    fn unsupported_operands_name_both_types() {
+        let err = run("return true + [1]").err().unwrap();
+        assert_eq!(err.message, "Type mismatch in addition: Boolean + Array");
+        let err = run("return { num a = 1 } + 1").err().unwrap();
+        assert_eq!(err.message, "Type mismatch in addition: Map + Integer");
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let err = run("return 9223372036854775807 + 1").err().unwrap();
+        assert_eq!(
+            err.message,
+            "Integer overflow in addition: 9223372036854775807 + 1"
+        );
+    }
+}
@@ -1,19 +1,16 @@
 use crate::ast::environment::Value;
 
+use super::operands::mismatch;
 use super::Expression;
 
 pub fn and(left: Box<Expression>, right: Box<Expression>) -> Expression {
     Box::new(move |ctx, scope| {
-        let left_value = left(ctx, scope);
-        let right_value = right(ctx, scope);
+        let left_value = left(ctx, scope)?;
+        let right_value = right(ctx, scope)?;
 
         match (&left_value, &right_value) {
-            (Value::Bool(l), Value::Bool(r)) => Value::Bool(*l && *r),
-            _ => panic!(
-                "Type mismatch in logical OR: {} || {}",
-                left_value.get_type(),
-                right_value.get_type()
-            ),
+            (Value::Bool(l), Value::Bool(r)) => Ok(Value::Bool(*l && *r)),
+            _ => Err(mismatch("logical AND", "&&", &left_value, &right_value)),
         }
     })
 }
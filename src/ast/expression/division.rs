@@ -1,30 +1,62 @@
 use crate::ast::environment::Value;
+use crate::error::DaisyError;
 
+use super::operands::{mismatch, operands, overflow, Operands};
 use super::Expression;
 
 pub fn division(left: Box<Expression>, right: Box<Expression>) -> Expression {
     Box::new(move |ctx, scope| {
-        let left_value = left(ctx, scope);
-        let right_value = right(ctx, scope);
+        let left_value = left(ctx, scope)?;
+        let right_value = right(ctx, scope)?;
 
-        match (&left_value, &right_value) {
-            (Value::Number(l), Value::Number(r)) => {
-                if *r == 0 {
-                    panic!("Division by zero");
+        match operands(&left_value, &right_value) {
+            Some(Operands::Number(l, r)) => {
+                if r == 0 {
+                    return Err(DaisyError::new("Division by zero"));
                 }
-                Value::Number(l / r)
+                l.checked_div(r)
+                    .map(Value::Number)
+                    .ok_or_else(|| overflow("division", "/", l, r))
             }
-            (Value::Float(l), Value::Float(r)) => {
-                if *r == 0.0 {
-                    panic!("Division by zero");
+            Some(Operands::Float(l, r)) => {
+                if r == 0.0 {
+                    return Err(DaisyError::new("Division by zero"));
                 }
-                Value::Float(l / r)
+                Ok(Value::Float(l / r))
             }
-            _ => panic!(
-                "Type mismatch in division: {} / {}",
-                left_value.get_type(),
-                right_value.get_type()
-            ),
+            None => Err(mismatch("division", "/", &left_value, &right_value)),
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::environment::Value;
+    use crate::ast::test::run;
+
+    #[test]
+    fn numbers_promote_to_floats() {
+        assert!(run("return 7 / 2").unwrap() == Value::Number(3));
+        assert!(run("return 7 / 2.0").unwrap() == Value::Float(3.5));
+        assert!(run("return 7.0 / 2").unwrap() == Value::Float(3.5));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            run("return 1 / 0").err().unwrap().message,
+            "Division by zero"
+        );
+        assert_eq!(
+            run("num big = -9223372036854775807 - 1 return big / -1")
+                .err()
+                .unwrap()
+                .message,
+            "Integer overflow in division: -9223372036854775808 / -1"
+        );
+        assert_eq!(
+            run("return nil / 1").err().unwrap().message,
+            "Type mismatch in division: Nil / Integer"
+        );
+    }
+}
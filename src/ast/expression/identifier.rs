@@ -1,12 +1,17 @@
 use crate::ast::environment::Value;
+use crate::resolver::data;
 
 use super::Expression;
 
 pub fn identifier(location: Vec<String>) -> Expression {
-    Box::new(move |_ctx, scope| {
+    Box::new(move |ctx, scope| {
         let first = location
             .first()
             .expect("Identifier must have at least one part");
+        // the builtin `data` is read-only, a variable shadowing it doesn't depend on the data directory
+        if first == "data" && scope.is_read_only(first) {
+            data::record(ctx);
+        }
         let mut value: Option<&Value> = scope.get(first);
         for part in location.iter().skip(1) {
             value = if let Some(Value::Map(map)) = value {
@@ -17,9 +22,9 @@ pub fn identifier(location: Vec<String>) -> Expression {
         }
 
         if let Some(value) = value {
-            value.clone()
+            Ok(value.clone())
         } else {
-            Value::Nil
+            Ok(Value::Nil)
         }
     })
 }
@@ -1,20 +1,17 @@
 use crate::ast::environment::Value;
 
+use super::operands::{mismatch, operands, Operands};
 use super::Expression;
 
 pub fn lessthan(left: Box<Expression>, right: Box<Expression>) -> Expression {
     Box::new(move |ctx, scope| {
-        let left_value = left(ctx, scope);
-        let right_value = right(ctx, scope);
+        let left_value = left(ctx, scope)?;
+        let right_value = right(ctx, scope)?;
 
-        match (&left_value, &right_value) {
-            (Value::Number(l), Value::Number(r)) => Value::Bool(l < r),
-            (Value::Float(l), Value::Float(r)) => Value::Bool(l < r),
-            _ => panic!(
-                "Type mismatch in less than: {} < {}",
-                left_value.get_type(),
-                right_value.get_type()
-            ),
+        match operands(&left_value, &right_value) {
+            Some(Operands::Number(l, r)) => Ok(Value::Bool(l < r)),
+            Some(Operands::Float(l, r)) => Ok(Value::Bool(l < r)),
+            None => Err(mismatch("less than", "<", &left_value, &right_value)),
         }
     })
 }
@@ -7,13 +7,13 @@ pub fn map(entries: Vec<(Type, String, Option<Expression>)>) -> Expression {
         let mut map = Scope::new();
         for entry in entries.iter() {
             let value = if let Some(expr) = &entry.2 {
-                expr(ctx, scope)
+                expr(ctx, scope)?
             } else {
                 Value::Nil
             };
-            map.define(entry.0.clone(), entry.1.clone(), value);
+            map.define(entry.0.clone(), entry.1.clone(), value)?;
         }
 
-        Value::Map(map)
+        Ok(Value::Map(map))
     })
 }
@@ -0,0 +1,38 @@
+use crate::ast::environment::{Scope, Value};
+use crate::ast::statement::if_statement::run_body;
+use crate::ast::statement::match_statement::{select, MatchArm, MatchBody};
+use crate::ast::statement::Result;
+use crate::error::DaisyError;
+
+use super::Expression;
+
+pub fn match_expression(value: Expression, arms: Vec<MatchArm>) -> Expression {
+    Box::new(move |ctx, scope| {
+        let value = value(ctx, scope)?;
+        let Some((pattern, body)) = select(&arms, &value) else {
+            return Ok(Value::Nil);
+        };
+
+        scope.wrap(|inner_scope| {
+            pattern.bind(inner_scope, value)?;
+            let statements = match body {
+                MatchBody::Expression(expression) => return expression(ctx, inner_scope),
+                MatchBody::Block(statements) => statements,
+            };
+
+            match run_body(ctx, inner_scope, statements)? {
+                Result::Return(value) => Ok(value),
+                Result::Collect(values) => {
+                    let mut array = Scope::new();
+                    for value in values {
+                        array.array_push(value);
+                    }
+                    Ok(Value::Array(array))
+                }
+                Result::NOP => Ok(Value::Nil),
+                Result::Break => Err(DaisyError::new("Break statement outside of loop")),
+                Result::Continue => Err(DaisyError::new("Continue statement outside of loop")),
+            }
+        })
+    })
+}
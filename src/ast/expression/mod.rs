@@ -1,8 +1,9 @@
 use crate::context::Context;
+use crate::error::DaisyResult;
 
 use super::environment::{Scope, Value};
 
-pub type Expression = Box<dyn Fn(&mut Context, &mut Scope) -> Value + 'static>;
+pub type Expression = Box<dyn Fn(&mut Context, &mut Scope) -> DaisyResult<Value> + 'static>;
 
 pub mod addition;
 pub mod and;
@@ -16,12 +17,19 @@ pub mod identifier;
 pub mod lessthan;
 pub mod lessthanorequal;
 pub mod map;
+pub mod match_expression;
+pub mod modulo;
 pub mod multiplication;
+pub mod negation;
+pub mod not;
 pub mod notequal;
+pub mod operands;
 pub mod or;
 pub mod scope_entry;
 pub mod script;
+pub mod spanned;
 pub mod subtraction;
+pub mod template;
 pub mod value;
 
 pub use addition::addition;
@@ -36,10 +44,16 @@ pub use identifier::identifier;
 pub use lessthan::lessthan;
 pub use lessthanorequal::lessthanorequal;
 pub use map::map;
+pub use match_expression::match_expression;
+pub use modulo::modulo;
 pub use multiplication::multiplication;
+pub use negation::negation;
+pub use not::not;
 pub use notequal::notequal;
 pub use or::or;
 pub use scope_entry::scope_entry;
 pub use script::script;
+pub use spanned::spanned;
 pub use subtraction::subtraction;
+pub use template::template;
 pub use value::value;
@@ -0,0 +1,60 @@
+use crate::ast::environment::Value;
+use crate::error::DaisyError;
+
+use super::operands::{mismatch, operands, overflow, Operands};
+use super::Expression;
+
+pub fn modulo(left: Box<Expression>, right: Box<Expression>) -> Expression {
+    Box::new(move |ctx, scope| {
+        let left_value = left(ctx, scope)?;
+        let right_value = right(ctx, scope)?;
+
+        match operands(&left_value, &right_value) {
+            Some(Operands::Number(l, r)) => {
+                if r == 0 {
+                    return Err(DaisyError::new("Modulo by zero"));
+                }
+                l.checked_rem(r)
+                    .map(Value::Number)
+                    .ok_or_else(|| overflow("modulo", "%", l, r))
+            }
+            Some(Operands::Float(l, r)) => {
+                if r == 0.0 {
+                    return Err(DaisyError::new("Modulo by zero"));
+                }
+                Ok(Value::Float(l % r))
+            }
+            None => Err(mismatch("modulo", "%", &left_value, &right_value)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::environment::Value;
+    use crate::ast::test::run;
+
+    #[test]
+    fn remainders() {
+        assert!(run("return 7 % 3").unwrap() == Value::Number(1));
+        assert!(run("return -7 % 3").unwrap() == Value::Number(-1));
+        assert!(run("return 7.5 % 2").unwrap() == Value::Float(1.5));
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let err = run("num big = -9223372036854775807 - 1 return big % -1")
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.message,
+            "Integer overflow in modulo: -9223372036854775808 % -1"
+        );
+    }
+
+    #[test]
+    fn modulo_by_zero_is_an_error() {
+        let err = run("return 1 % 0").err().unwrap();
+        assert_eq!(err.message, "Modulo by zero");
+    }
+}
@@ -1,20 +1,51 @@
 use crate::ast::environment::Value;
 
+use super::operands::{mismatch, operands, overflow, Operands};
 use super::Expression;
 
 pub fn multiplication(left: Box<Expression>, right: Box<Expression>) -> Expression {
     Box::new(move |ctx, scope| {
-        let left_value = left(ctx, scope);
-        let right_value = right(ctx, scope);
+        let left_value = left(ctx, scope)?;
+        let right_value = right(ctx, scope)?;
 
-        match (&left_value, &right_value) {
-            (Value::Number(l), Value::Number(r)) => Value::Number(l * r),
-            (Value::Float(l), Value::Float(r)) => Value::Float(l * r),
-            _ => panic!(
-                "Type mismatch in multiplication: {} * {}",
-                left_value.get_type(),
-                right_value.get_type()
-            ),
+        match operands(&left_value, &right_value) {
+            Some(Operands::Number(l, r)) => l
+                .checked_mul(r)
+                .map(Value::Number)
+                .ok_or_else(|| overflow("multiplication", "*", l, r)),
+            Some(Operands::Float(l, r)) => Ok(Value::Float(l * r)),
+            None => Err(mismatch("multiplication", "*", &left_value, &right_value)),
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::environment::Value;
+    use crate::ast::test::run;
+
+    #[test]
+    fn numbers_promote_to_floats() {
+        assert!(run("return 3 * 4").unwrap() == Value::Number(12));
+        assert!(run("return 3 * 0.5").unwrap() == Value::Float(1.5));
+        assert!(run("return 0.5 * 3").unwrap() == Value::Float(1.5));
+    }
+
+    #[test]
+    fn unsupported_operands_name_both_types() {
+        let err = run(r#"return "a" * 2"#).err().unwrap();
+        assert_eq!(
+            err.message,
+            "Type mismatch in multiplication: String * Integer"
+        );
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let err = run("return 9223372036854775807 * 2").err().unwrap();
+        assert_eq!(
+            err.message,
+            "Integer overflow in multiplication: 9223372036854775807 * 2"
+        );
+    }
+}
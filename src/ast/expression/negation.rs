@@ -0,0 +1,18 @@
+use crate::ast::environment::Value;
+use crate::error::DaisyError;
+
+use super::Expression;
+
+pub fn negation(value: Box<Expression>) -> Expression {
+    Box::new(move |ctx, scope| match value(ctx, scope)? {
+        Value::Number(n) => n
+            .checked_neg()
+            .map(Value::Number)
+            .ok_or_else(|| DaisyError::new(format!("Overflow in negation: -{}", n))),
+        Value::Float(n) => Ok(Value::Float(-n)),
+        value => Err(DaisyError::new(format!(
+            "Type mismatch in negation: -{}",
+            value.get_type()
+        ))),
+    })
+}
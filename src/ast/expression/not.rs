@@ -0,0 +1,14 @@
+use crate::ast::environment::Value;
+use crate::error::DaisyError;
+
+use super::Expression;
+
+pub fn not(value: Box<Expression>) -> Expression {
+    Box::new(move |ctx, scope| match value(ctx, scope)? {
+        Value::Bool(b) => Ok(Value::Bool(!b)),
+        value => Err(DaisyError::new(format!(
+            "Type mismatch in logical NOT: !{}",
+            value.get_type()
+        ))),
+    })
+}
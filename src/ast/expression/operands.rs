@@ -0,0 +1,35 @@
+use crate::ast::environment::Value;
+use crate::error::DaisyError;
+
+pub enum Operands {
+    Number(i64, i64),
+    Float(f64, f64),
+}
+
+// integers stay integers, as soon as one of the sides is a float both sides are promoted
+pub fn operands(left: &Value, right: &Value) -> Option<Operands> {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => Some(Operands::Number(*l, *r)),
+        (Value::Number(l), Value::Float(r)) => Some(Operands::Float(*l as f64, *r)),
+        (Value::Float(l), Value::Number(r)) => Some(Operands::Float(*l, *r as f64)),
+        (Value::Float(l), Value::Float(r)) => Some(Operands::Float(*l, *r)),
+        _ => None,
+    }
+}
+
+pub fn mismatch(operation: &str, operator: &str, left: &Value, right: &Value) -> DaisyError {
+    DaisyError::new(format!(
+        "Type mismatch in {}: {} {} {}",
+        operation,
+        left.get_type(),
+        operator,
+        right.get_type()
+    ))
+}
+
+pub fn overflow(operation: &str, operator: &str, left: i64, right: i64) -> DaisyError {
+    DaisyError::new(format!(
+        "Integer overflow in {}: {} {} {}",
+        operation, left, operator, right
+    ))
+}
@@ -1,6 +1,7 @@
 use std::process::Command;
 
 use crate::ast::environment::Value;
+use crate::error::DaisyError;
 
 use super::Expression;
 
@@ -11,8 +12,13 @@ pub fn script(script: Box<String>) -> Expression {
             .arg(script.to_string())
             .output();
         match result {
-            Ok(output) => Value::String(String::from_utf8_lossy(&output.stdout).trim().to_string()),
-            Err(e) => panic!("Failed to execute script '{}': {}", script, e),
+            Ok(output) => Ok(Value::String(
+                String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            )),
+            Err(e) => Err(DaisyError::new(format!(
+                "Failed to execute script '{}': {}",
+                script, e
+            ))),
         }
     })
 }
@@ -0,0 +1,8 @@
+use std::{path::Path, rc::Rc};
+
+use super::Expression;
+
+pub fn spanned(file: &Rc<Path>, span: (usize, usize), expression: Expression) -> Expression {
+    let file = file.clone();
+    Box::new(move |ctx, scope| expression(ctx, scope).map_err(|err| err.with_location(&file, span)))
+}
@@ -1,20 +1,44 @@
 use crate::ast::environment::Value;
 
+use super::operands::{mismatch, operands, overflow, Operands};
 use super::Expression;
 
 pub fn subtraction(left: Box<Expression>, right: Box<Expression>) -> Expression {
     Box::new(move |ctx, scope| {
-        let left_value = left(ctx, scope);
-        let right_value = right(ctx, scope);
+        let left_value = left(ctx, scope)?;
+        let right_value = right(ctx, scope)?;
 
-        match (&left_value, &right_value) {
-            (Value::Number(l), Value::Number(r)) => Value::Number(l - r),
-            (Value::Float(l), Value::Float(r)) => Value::Float(l - r),
-            _ => panic!(
-                "Type mismatch in subtraction: {} - {}",
-                left_value.get_type(),
-                right_value.get_type()
-            ),
+        match operands(&left_value, &right_value) {
+            Some(Operands::Number(l, r)) => l
+                .checked_sub(r)
+                .map(Value::Number)
+                .ok_or_else(|| overflow("subtraction", "-", l, r)),
+            Some(Operands::Float(l, r)) => Ok(Value::Float(l - r)),
+            None => Err(mismatch("subtraction", "-", &left_value, &right_value)),
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::environment::Value;
+    use crate::ast::test::run;
+
+    #[test]
+    fn numbers_promote_to_floats() {
+        assert!(run("return 5 - 2").unwrap() == Value::Number(3));
+        assert!(run("return 5 - 0.5").unwrap() == Value::Float(4.5));
+        assert!(run("return 5.5 - 1").unwrap() == Value::Float(4.5));
+    }
+
+    #[test]
+    fn unsupported_operands_name_both_types() {
+        let err = run(r#"return "a" - 1"#).err().unwrap();
+        assert_eq!(
+            err.message,
+            "Type mismatch in subtraction: String - Integer"
+        );
+        let err = run("return [1] - [1]").err().unwrap();
+        assert_eq!(err.message, "Type mismatch in subtraction: Array - Array");
+    }
+}
@@ -0,0 +1,24 @@
+use crate::ast::environment::Value;
+
+use super::Expression;
+
+pub enum Segment {
+    Text(String),
+    Interpolation(Expression),
+}
+
+pub fn template(segments: Vec<Segment>) -> Expression {
+    Box::new(move |ctx, scope| {
+        let mut result = String::new();
+        for segment in &segments {
+            match segment {
+                Segment::Text(text) => result.push_str(text),
+                Segment::Interpolation(expression) => {
+                    let value = expression(ctx, scope)?;
+                    result.push_str(&value.render(ctx, scope)?);
+                }
+            }
+        }
+        Ok(Value::String(result))
+    })
+}
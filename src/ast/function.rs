@@ -1,4 +1,5 @@
 use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
 
 use super::{
     environment::{Scope, Type, Value},
@@ -11,12 +12,18 @@ pub fn call_function(
     value: &Value,
     args: &Vec<Expression>,
     scope: &mut Scope,
-) -> Value {
+) -> DaisyResult<Value> {
     if !Type::matches(&Type::Function, &value) {
-        panic!("Expected a function, got {}", value.get_type());
+        return Err(DaisyError::new(format!(
+            "Expected a function, got {}",
+            value.get_type()
+        )));
     }
 
-    let args: Vec<Value> = args.iter().map(|arg| arg(ctx, scope)).collect();
+    let args: Vec<Value> = args
+        .iter()
+        .map(|arg| arg(ctx, scope))
+        .collect::<DaisyResult<_>>()?;
 
     match value {
         Value::Function(func, params, return_type, body) => {
@@ -36,32 +43,32 @@ pub fn call_function(
                     &mut inner_scope,
                 )
             } else {
-                panic!(
+                Err(DaisyError::new(format!(
                     "Expected a function in scoped value, got {}",
                     value.get_type()
-                );
+                )))
             }
         }
-        _ => Value::Nil,
+        _ => Ok(Value::Nil),
     }
 }
 
 fn run_function(
     ctx: &mut Context,
-    func: &fn(&mut Context, &Vec<Statement>, &Vec<Value>, &mut Scope) -> Value,
+    func: &fn(&mut Context, &Vec<Statement>, &Vec<Value>, &mut Scope) -> DaisyResult<Value>,
     params: &Vec<(Type, String, Option<Expression>)>,
     return_type: &Type,
     args: &Vec<Value>,
     body: &Vec<Statement>,
     scope: &mut Scope,
-) -> Value {
+) -> DaisyResult<Value> {
     scope.wrap(|inner_scope| {
         for param in params {
-            inner_scope.define(param.0.clone(), param.1.clone(), Value::Nil);
+            inner_scope.define(param.0.clone(), param.1.clone(), Value::Nil)?;
             if let Some(expr) = &param.2 {
                 // If the parameter has a default value, evaluate it
-                let default_value = expr(ctx, inner_scope);
-                inner_scope.set(param.1.clone(), default_value);
+                let default_value = expr(ctx, inner_scope)?;
+                inner_scope.set(param.1.clone(), default_value)?;
             }
         }
 
@@ -70,20 +77,20 @@ fn run_function(
             for i in 0..args.len() {
                 let arg = args.get(i).unwrap();
                 let param = params.get(i).unwrap();
-                inner_scope.set(param.1.clone(), arg.clone());
+                inner_scope.set(param.1.clone(), arg.clone())?;
             }
         }
 
-        let return_value = func(ctx, &body, &args, inner_scope);
+        let return_value = func(ctx, &body, &args, inner_scope)?;
 
         if Type::matches(&return_type, &return_value) {
-            return_value
+            Ok(return_value)
         } else {
-            panic!(
+            Err(DaisyError::new(format!(
                 "Type mismatch: expected {}, got {}",
                 return_type,
                 return_value.get_type()
-            );
+            )))
         }
     })
 }
@@ -93,29 +100,29 @@ pub fn default_function(
     stmts: &Vec<Statement>,
     _: &Vec<Value>,
     scope: &mut Scope,
-) -> Value {
+) -> DaisyResult<Value> {
     for stmt in stmts {
-        match stmt(ctx, scope) {
+        match stmt(ctx, scope)? {
             Result::Return(value) => {
-                return value;
+                return Ok(value);
             }
             Result::Collect(value) => {
                 let mut array = Scope::new();
                 for val in value {
                     array.array_push(val);
                 }
-                return Value::Array(array);
+                return Ok(Value::Array(array));
             }
             Result::Break => {
-                panic!("Break statement outside of loop");
+                return Err(DaisyError::new("Break statement outside of loop"));
             }
             Result::Continue => {
-                panic!("Continue statement outside of loop");
+                return Err(DaisyError::new("Continue statement outside of loop"));
             }
             Result::NOP => {
                 // Do nothing, continue processing
             }
         }
     }
-    Value::Nil
+    Ok(Value::Nil)
 }
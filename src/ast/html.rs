@@ -1,11 +1,15 @@
-use crate::{ast::node::Node, context::Context};
-use std::collections::HashMap;
+use crate::{
+    ast::node::Node,
+    context::Context,
+    error::{DaisyError, DaisyResult},
+};
 
 use super::environment::Scope;
 
 pub struct Element {
     tag: String,
-    attributes: HashMap<String, Vec<Node>>,
+    // kept in source order so the output is the same for every build
+    attributes: Vec<(String, Vec<Node>)>,
     content: Vec<Node>,
 }
 
@@ -14,7 +18,7 @@ impl Element {
         let mut element = Element {
             tag,
             content,
-            attributes: HashMap::new(),
+            attributes: vec![],
         };
 
         element.add_attribute(attributes);
@@ -24,50 +28,121 @@ impl Element {
 
     pub fn add_attribute(&mut self, attributes: Vec<(String, Vec<Node>)>) {
         for (key, value) in attributes {
-            match key.as_str() {
-                "id" => {
-                    self.attributes.insert(key, value);
-                }
-                _ => {
-                    if let Some(existing) = self.attributes.get_mut(&key) {
-                        existing.extend(value);
-                    } else {
-                        self.attributes.insert(key, value);
-                    }
-                }
+            let existing = self
+                .attributes
+                .iter_mut()
+                .find(|(existing, _)| *existing == key);
+
+            match (key.as_str(), existing) {
+                ("id", Some((_, existing))) => *existing = value,
+                (_, Some((_, existing))) => existing.extend(value),
+                (_, None) => self.attributes.push((key, value)),
             };
         }
     }
 
-    pub fn render(&self, ctx: &mut Context, scope: &mut Scope) -> String {
-        let attributes: HashMap<String, String> = self
-            .attributes
-            .iter()
-            .map(|(k, v)| {
-                let value = v
-                    .iter()
-                    .map(|node| node(ctx, scope))
-                    .collect::<Vec<String>>()
-                    .join(" ");
-                (k.clone(), value)
-            })
-            .collect();
+    pub fn render(&self, ctx: &mut Context, scope: &mut Scope) -> DaisyResult<String> {
+        let raw_text = std::mem::replace(&mut ctx.raw_text, false);
+        let attributes = self.render_attributes(ctx, scope);
+        ctx.raw_text = raw_text;
+        let attributes = attributes?;
 
         let mut output = if attributes.is_empty() {
-            format!("<{}>", self.tag)
+            format!("<{}", self.tag)
         } else {
             let attrs: String = attributes
                 .iter()
-                .map(|(k, v)| format!("{}=\"{}\"", k, v))
+                .map(|(k, v)| match v {
+                    Some(v) => format!("{}=\"{}\"", k, v),
+                    None => k.clone(),
+                })
                 .collect::<Vec<String>>()
                 .join(" ");
-            format!("<{} {}>", self.tag, attrs)
+            format!("<{} {}", self.tag, attrs)
         };
 
-        for node in &self.content {
-            output.push_str(node(ctx, scope).as_str());
+        // xml has no void elements, any element without content is closed right away
+        if ctx.xml {
+            if self.content.is_empty() {
+                output.push_str("/>");
+                return Ok(output);
+            }
+        } else if VOID.contains(&self.tag.as_str()) {
+            if !self.content.is_empty() {
+                return Err(DaisyError::new(format!(
+                    "Void element <{}> cannot have content",
+                    self.tag
+                )));
+            }
+            output.push('>');
+            return Ok(output);
         }
+        output.push('>');
+
+        let raw_text = std::mem::replace(
+            &mut ctx.raw_text,
+            !ctx.xml && RAW_TEXT.contains(&self.tag.as_str()),
+        );
+        let content = self
+            .content
+            .iter()
+            .map(|node| node(ctx, scope))
+            .collect::<DaisyResult<Vec<String>>>();
+        ctx.raw_text = raw_text;
+
+        output.push_str(&content?.concat());
         output.push_str(&format!("</{}>", self.tag));
-        output
+        Ok(output)
+    }
+
+    fn render_attributes(
+        &self,
+        ctx: &mut Context,
+        scope: &mut Scope,
+    ) -> DaisyResult<Vec<(String, Option<String>)>> {
+        let mut attributes = vec![];
+        for (k, v) in self.attributes.iter() {
+            // attributes without a value are boolean attributes like `disabled`
+            if v.is_empty() {
+                attributes.push((k.clone(), None));
+                continue;
+            }
+
+            let value = v
+                .iter()
+                .map(|node| node(ctx, scope))
+                .collect::<DaisyResult<Vec<String>>>()?
+                .join(" ");
+            attributes.push((k.clone(), Some(value)));
+        }
+        Ok(attributes)
+    }
+}
+
+// elements that can't have any content and are written without a closing tag
+pub const VOID: [&str; 13] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+// the contents of these elements are not parsed as html by browsers so they are never escaped
+const RAW_TEXT: [&str; 2] = ["script", "style"];
+
+pub fn escape(ctx: &Context, input: &str) -> String {
+    if ctx.raw_text {
+        return input.to_string();
+    }
+
+    let mut output = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            _ => output.push(c),
+        }
     }
+    output
 }
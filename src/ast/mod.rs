@@ -6,3 +6,6 @@ pub mod html;
 pub mod node;
 pub mod statement;
 pub mod strings;
+
+#[cfg(test)]
+pub mod test;
@@ -2,5 +2,5 @@ use super::Node;
 use crate::ast::expression::Expression;
 
 pub fn insert(expr: Expression) -> Node {
-    Box::new(move |ctx, scope| expr(ctx, scope).render(ctx, scope))
+    Box::new(move |ctx, scope| expr(ctx, scope)?.render_html(ctx, scope))
 }
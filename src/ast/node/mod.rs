@@ -1,16 +1,19 @@
 use crate::context::Context;
+use crate::error::DaisyResult;
 
 use super::environment::Scope;
 
-pub type Node = Box<dyn Fn(&mut Context, &mut Scope) -> String + 'static>;
+pub type Node = Box<dyn Fn(&mut Context, &mut Scope) -> DaisyResult<String> + 'static>;
 
 mod element;
 mod insert;
 mod logic;
+mod spanned;
 mod text;
 
 pub use element::element;
 pub use insert::insert;
 pub use logic::logic_expression;
 pub use logic::logic_statement;
+pub use spanned::spanned;
 pub use text::text;
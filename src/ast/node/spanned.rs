@@ -0,0 +1,8 @@
+use std::{path::Path, rc::Rc};
+
+use super::Node;
+
+pub fn spanned(file: &Rc<Path>, span: (usize, usize), node: Node) -> Node {
+    let file = file.clone();
+    Box::new(move |ctx, scope| node(ctx, scope).map_err(|err| err.with_location(&file, span)))
+}
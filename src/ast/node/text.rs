@@ -1,5 +1,6 @@
 use super::Node;
+use crate::ast::html;
 
 pub fn text(text: String) -> Node {
-    Box::new(move |_ctx, _scope| text.clone())
+    Box::new(move |ctx, _scope| Ok(html::escape(ctx, &text)))
 }
@@ -2,27 +2,35 @@ use super::{Result, Statement};
 use crate::ast::environment::Value;
 use crate::ast::expression::Expression;
 use crate::ast::function::call_function;
+use crate::error::DaisyError;
 
 pub fn call(identifier: Expression, arguments: Vec<Expression>) -> Statement {
     Box::new(move |ctx, scope| {
-        let function = identifier(ctx, scope);
+        let function = identifier(ctx, scope)?;
         match function {
             Value::Function(..) => {
-                call_function(ctx, &function, &arguments, scope);
+                call_function(ctx, &function, &arguments, scope)?;
             }
             Value::Scoped(inner_scope, value) => {
                 let mut inner_scope = inner_scope.clone();
-                let (func, vars, return_type, args) = value.try_into_function().unwrap();
+                let (func, vars, return_type, args) = value
+                    .try_into_function()
+                    .ok_or_else(|| DaisyError::new("Expected a function in scoped value"))?;
                 call_function(
                     ctx,
                     &Value::Function(func, vars, return_type, args),
                     &arguments,
                     &mut inner_scope,
-                );
+                )?;
+            }
+            _ => {
+                return Err(DaisyError::new(format!(
+                    "Expected a function, got {}",
+                    function.get_type()
+                )))
             }
-            _ => panic!("Expected a function, got {}", function.get_type()),
         }
 
-        Result::NOP
+        Ok(Result::NOP)
     })
 }
@@ -3,7 +3,7 @@ use crate::ast::expression::Expression;
 
 pub fn collect(expression: Expression) -> Statement {
     Box::new(move |ctx, scope| {
-        let value = expression(ctx, scope);
-        Result::Collect(vec![value])
+        let value = expression(ctx, scope)?;
+        Ok(Result::Collect(vec![value]))
     })
 }
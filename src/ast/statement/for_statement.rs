@@ -10,16 +10,16 @@ pub fn for_statement(
 ) -> Statement {
     Box::new(move |ctx, scope| {
         scope.wrap(|inner_scope| {
-            init(ctx, inner_scope);
+            init(ctx, inner_scope)?;
 
             let mut collected_values = vec![];
             'mainloop: loop {
-                if let Value::Bool(false) = condition(ctx, inner_scope) {
+                if let Value::Bool(false) = condition(ctx, inner_scope)? {
                     break;
                 }
 
                 for stmt in body.iter() {
-                    let result = stmt(ctx, inner_scope);
+                    let result = stmt(ctx, inner_scope)?;
                     match result {
                         Result::Continue => {
                             continue 'mainloop;
@@ -29,18 +29,18 @@ pub fn for_statement(
                         }
                         Result::NOP => {}
                         _ => {
-                            return result;
+                            return Ok(result);
                         }
                     }
 
-                    increment(ctx, inner_scope);
+                    increment(ctx, inner_scope)?;
                 }
             }
 
             if collected_values.is_empty() {
-                Result::NOP
+                Ok(Result::NOP)
             } else {
-                Result::Collect(collected_values)
+                Ok(Result::Collect(collected_values))
             }
         })
     })
@@ -1,34 +1,129 @@
 use super::{Result, Statement};
-use crate::ast::environment::Value;
+use crate::ast::environment::{Scope, Value};
 use crate::ast::expression::Expression;
+use crate::context::Context;
+use crate::error::DaisyResult;
 
-pub fn if_statement(condition: Expression, body: Vec<Statement>) -> Statement {
+pub fn if_statement(
+    branches: Vec<(Expression, Vec<Statement>)>,
+    else_body: Option<Vec<Statement>>,
+) -> Statement {
     Box::new(move |ctx, scope| {
-        let condition_value = condition(ctx, scope);
-        let mut collected_values = vec![];
-        let mut result = Result::NOP;
-        if let Value::Bool(true) = condition_value {
-            result = scope.wrap(|inner_scope| {
-                for stmt in body.iter() {
-                    let result = stmt(ctx, inner_scope);
-                    match result {
-                        Result::Collect(values) => {
-                            collected_values.extend(values);
-                        }
-                        Result::NOP => {}
-                        _ => {
-                            return result;
-                        }
-                    }
-                }
-                return Result::NOP;
-            });
+        for (condition, body) in branches.iter() {
+            if let Value::Bool(true) = condition(ctx, scope)? {
+                return run_body(ctx, scope, body);
+            }
         }
 
-        if collected_values.is_empty() {
-            result
-        } else {
-            Result::Collect(collected_values)
+        if let Some(body) = &else_body {
+            return run_body(ctx, scope, body);
         }
+
+        Ok(Result::NOP)
     })
 }
+
+pub fn run_body(ctx: &mut Context, scope: &mut Scope, body: &[Statement]) -> DaisyResult<Result> {
+    let mut collected_values = vec![];
+    let result = scope.wrap(|inner_scope| -> DaisyResult<Result> {
+        for stmt in body.iter() {
+            let result = stmt(ctx, inner_scope)?;
+            match result {
+                Result::Collect(values) => {
+                    collected_values.extend(values);
+                }
+                Result::NOP => {}
+                _ => {
+                    return Ok(result);
+                }
+            }
+        }
+        Ok(Result::NOP)
+    })?;
+
+    if collected_values.is_empty() {
+        Ok(result)
+    } else {
+        Ok(Result::Collect(collected_values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::environment::Value;
+    use crate::ast::test::{render, run};
+
+    fn kind(n: i64) -> Value {
+        run(&format!(
+            r#"
+            num n = {}
+            if n < 0 {{
+                return "negative"
+            }} else if n == 0 {{
+                return "zero"
+            }} else if n < 10 {{
+                return "small"
+            }} else {{
+                return "large"
+            }}
+            "#,
+            n
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn else_if_chains_pick_the_first_matching_branch() {
+        assert!(kind(-1) == Value::String("negative".into()));
+        assert!(kind(0) == Value::String("zero".into()));
+        assert!(kind(5) == Value::String("small".into()));
+        assert!(kind(50) == Value::String("large".into()));
+    }
+
+    #[test]
+    fn else_branches_collect_in_markup() {
+        let html = render(
+            r#"
+            bool draft = false
+            return : div {
+                if draft {
+                    collect : p > "draft"
+                } else {
+                    collect : p > "published"
+                }
+            }
+        "#,
+        )
+        .unwrap();
+        assert_eq!(html, "<div><p>published</p></div>");
+    }
+
+    #[test]
+    fn nothing_runs_when_no_branch_matches() {
+        let value = run(r#"
+            str result = "unchanged"
+            if false {
+                result = "if"
+            } else if 1 == 2 {
+                result = "else if"
+            }
+            return result
+        "#)
+        .unwrap();
+        assert!(value == Value::String("unchanged".into()));
+
+        let html = render(
+            r#"
+            return : div {
+                if false {
+                    collect : p > "if"
+                } else if false {
+                    collect : p > "else if"
+                }
+            }
+        "#,
+        )
+        .unwrap();
+        assert_eq!(html, "<div></div>");
+    }
+}
@@ -1,6 +1,7 @@
 use super::{Result, Statement};
 use crate::ast::environment::{Type, Value};
 use crate::ast::expression::Expression;
+use crate::error::DaisyError;
 
 pub fn iter_statement(
     identifiers: (String, Option<String>),
@@ -10,37 +11,45 @@ pub fn iter_statement(
     Box::new(move |ctx, scope| {
         scope.wrap(|inner_scope| {
             let (key_name, value_name) = identifiers.clone();
-            let var = iterable(ctx, inner_scope);
-            let (_scope, indices) = match var {
+            let var = iterable(ctx, inner_scope)?;
+            let (_scope, indices, is_array) = match var {
                 Value::Array(mut list) => {
                     let indices = list.get_indices();
-                    (list, indices)
+                    (list, indices, true)
                 }
                 Value::Map(mut map) => {
                     let keys = map.get_keys();
-                    (map, keys)
+                    (map, keys, false)
                 }
                 _ => {
-                    panic!("Expected an array or map, got {}", var);
+                    return Err(DaisyError::new(format!(
+                        "Expected an array or map, got {}",
+                        var
+                    )));
                 }
             };
 
             let mut collected_values = vec![];
-            inner_scope.define(Type::Any, key_name.to_string(), Value::Nil);
+            inner_scope.define(Type::Any, key_name.to_string(), Value::Nil)?;
             if let Some(value_name) = &value_name {
-                inner_scope.define(Type::Any, value_name.to_string(), Value::Nil);
+                inner_scope.define(Type::Any, value_name.to_string(), Value::Nil)?;
             }
             'mainloop: for index in indices {
-                inner_scope.set(key_name.to_string(), Value::String(index.clone()));
+                // array indices are numbers so they can be used in arithmetic, map keys are strings
+                let key = match index.parse::<i64>() {
+                    Ok(index) if is_array => Value::Number(index),
+                    _ => Value::String(index.clone()),
+                };
+                inner_scope.set(key_name.to_string(), key)?;
                 if let Some(value_name) = &value_name {
                     inner_scope.set(
                         value_name.to_string(),
                         _scope.get(&index).unwrap_or(&Value::Nil).clone(),
-                    );
+                    )?;
                 }
 
                 for statement in body.iter() {
-                    let result = statement(ctx, inner_scope);
+                    let result = statement(ctx, inner_scope)?;
                     match result {
                         Result::Continue => {
                             continue 'mainloop;
@@ -50,17 +59,60 @@ pub fn iter_statement(
                         }
                         Result::NOP => {}
                         _ => {
-                            return result;
+                            return Ok(result);
                         }
                     }
                 }
             }
 
             if collected_values.is_empty() {
-                return Result::NOP;
+                return Ok(Result::NOP);
             } else {
-                return Result::Collect(collected_values);
+                return Ok(Result::Collect(collected_values));
             }
         })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::environment::Value;
+    use crate::ast::test::{render, run};
+
+    #[test]
+    fn array_indices_are_numbers() {
+        let html = render(
+            r#"
+            list rows = ["a", "b", "c"]
+            return : ul {
+                for i, row in rows {
+                    if i % 2 == 0 {
+                        collect : li.even > @{row}
+                    } else {
+                        collect : li.odd > @{row}
+                    }
+                }
+            }
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            html,
+            r#"<ul><li class="even">a</li><li class="odd">b</li><li class="even">c</li></ul>"#
+        );
+    }
+
+    #[test]
+    fn map_keys_are_strings() {
+        let value = run(r#"
+            map m = { num a = 1 }
+            str keys = ""
+            for key, value in m {
+                keys = keys + key
+            }
+            return keys
+        "#)
+        .unwrap();
+        assert!(value == Value::String("a".into()));
+    }
+}
@@ -0,0 +1,116 @@
+use super::if_statement::run_body;
+use super::{Result, Statement};
+use crate::ast::environment::{Scope, Type, Value};
+use crate::ast::expression::Expression;
+use crate::error::DaisyResult;
+
+pub enum Pattern {
+    Value(Value),
+    Type(Type, Option<String>),
+    Default,
+}
+
+pub enum MatchBody {
+    Block(Vec<Statement>),
+    Expression(Expression),
+}
+
+pub type MatchArm = (Pattern, MatchBody);
+
+impl Pattern {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            Pattern::Value(pattern) => pattern == value,
+            Pattern::Type(type_, _) => value.get_type() == *type_,
+            Pattern::Default => true,
+        }
+    }
+
+    pub fn bind(&self, scope: &mut Scope, value: Value) -> DaisyResult<()> {
+        if let Pattern::Type(type_, Some(binding)) = self {
+            scope.define(type_.clone(), binding.clone(), value)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn select<'a>(arms: &'a [MatchArm], value: &Value) -> Option<&'a MatchArm> {
+    arms.iter().find(|(pattern, _)| pattern.matches(value))
+}
+
+// expression arms only produce output when the match is used in markup, as a statement they are
+// evaluated for their side effects
+pub fn match_statement(value: Expression, arms: Vec<MatchArm>, collect: bool) -> Statement {
+    Box::new(move |ctx, scope| {
+        let value = value(ctx, scope)?;
+        let Some((pattern, body)) = select(&arms, &value) else {
+            return Ok(Result::NOP);
+        };
+
+        scope.wrap(|inner_scope| {
+            pattern.bind(inner_scope, value)?;
+            match body {
+                MatchBody::Block(statements) => run_body(ctx, inner_scope, statements),
+                MatchBody::Expression(expression) => {
+                    let value = expression(ctx, inner_scope)?;
+                    if collect {
+                        Ok(Result::Collect(vec![value]))
+                    } else {
+                        Ok(Result::NOP)
+                    }
+                }
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::environment::Value;
+    use crate::ast::test::{render, run};
+
+    #[test]
+    fn statement_arms_do_not_collect() {
+        let value = run(r#"
+            str kind = "post"
+            match kind {
+                "post" => "ignored"
+                _ => "default"
+            }
+            return "returned"
+        "#)
+        .unwrap();
+        assert!(value == Value::String("returned".into()));
+    }
+
+    #[test]
+    fn statement_arms_run_side_effects() {
+        let value = run(r#"
+            num count = 1
+            match count {
+                1 { count = 2 }
+                _ => nil
+            }
+            return count
+        "#)
+        .unwrap();
+        assert!(value == Value::Number(2));
+    }
+
+    #[test]
+    fn markup_arms_collect() {
+        let html = render(
+            r#"
+            str kind = "post"
+            return : div {
+                match kind {
+                    "page" => "a page"
+                    str other => other
+                }
+            }
+        "#,
+        )
+        .unwrap();
+        assert_eq!(html, "<div>post</div>");
+    }
+}
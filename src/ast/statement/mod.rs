@@ -1,4 +1,5 @@
 use crate::context::Context;
+use crate::error::DaisyResult;
 
 use super::environment::{Scope, Value};
 
@@ -10,7 +11,7 @@ pub enum Result {
     NOP,
 }
 
-pub type Statement = Box<dyn Fn(&mut Context, &mut Scope) -> Result + 'static>;
+pub type Statement = Box<dyn Fn(&mut Context, &mut Scope) -> DaisyResult<Result> + 'static>;
 
 pub mod assign;
 pub mod break_statement;
@@ -21,7 +22,9 @@ pub mod define;
 pub mod for_statement;
 pub mod if_statement;
 pub mod iter_statement;
+pub mod match_statement;
 pub mod return_statement;
+pub mod spanned;
 
 pub use assign::assign;
 pub use break_statement::break_statement;
@@ -32,4 +35,6 @@ pub use define::define;
 pub use for_statement::for_statement;
 pub use if_statement::if_statement;
 pub use iter_statement::iter_statement;
+pub use match_statement::match_statement;
 pub use return_statement::return_statement;
+pub use spanned::spanned;
@@ -4,10 +4,10 @@ use crate::ast::expression::Expression;
 pub fn return_statement(expression: Option<Expression>) -> Statement {
     if let Some(expr) = expression {
         Box::new(move |ctx, scope| {
-            let value = expr(ctx, scope);
-            Result::Return(value)
+            let value = expr(ctx, scope)?;
+            Ok(Result::Return(value))
         })
     } else {
-        Box::new(|_ctx, _scope| Result::Break)
+        Box::new(|_ctx, _scope| Ok(Result::Break))
     }
 }
@@ -0,0 +1,8 @@
+use std::{path::Path, rc::Rc};
+
+use super::Statement;
+
+pub fn spanned(file: &Rc<Path>, span: (usize, usize), statement: Statement) -> Statement {
+    let file = file.clone();
+    Box::new(move |ctx, scope| statement(ctx, scope).map_err(|err| err.with_location(&file, span)))
+}
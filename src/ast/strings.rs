@@ -1,12 +1,45 @@
-pub fn parse_string(input: &str) -> String {
-    let input = input[1..input.len() - 1].to_string();
+use std::{path::Path, rc::Rc};
 
-    escape_characters(&input)
+use crate::ast::environment::Value;
+use crate::ast::expression::{self, template::Segment, Expression};
+use crate::error::{self, DaisyError, DaisyResult};
+use crate::grammar::ExpressionParser;
+
+// takes the place of an interpolation while the surrounding text is dedented and unescaped
+const PLACEHOLDER: char = '\0';
+
+thread_local! {
+    static EXPRESSION_PARSER: ExpressionParser = ExpressionParser::new();
+}
+
+pub enum Template {
+    Text(String),
+    Interpolated(Vec<Segment>),
+}
+
+impl Template {
+    pub fn into_expression(self) -> Expression {
+        match self {
+            Template::Text(text) => expression::value(Value::String(text)),
+            Template::Interpolated(segments) => expression::template(segments),
+        }
+    }
+}
+
+pub fn parse_string(file: &Rc<Path>, offset: usize, input: &str) -> DaisyResult<Template> {
+    let (input, interpolations) =
+        extract_interpolations(file, offset + 1, &input[1..input.len() - 1])?;
+
+    compile(file, &escape_characters(&input), interpolations)
 }
 
-pub fn parse_multiline_string(input: &str) -> String {
-    let s = input.to_string();
-    let inner = &s[2..s.len() - 2];
+pub fn parse_multiline_string(
+    file: &Rc<Path>,
+    offset: usize,
+    input: &str,
+) -> DaisyResult<Template> {
+    let (inner, interpolations) =
+        extract_interpolations(file, offset + 2, &input[2..input.len() - 2])?;
 
     let lines: Vec<&str> = inner.lines().collect();
     let indent = lines
@@ -21,7 +54,104 @@ pub fn parse_multiline_string(input: &str) -> String {
         .collect::<Vec<_>>();
     let lines = lines[1..lines.len() - 1].join("\n");
 
-    escape_characters(&lines)
+    compile(file, &escape_characters(&lines), interpolations)
+}
+
+// replaces every `${...}` with a placeholder and returns the source of each interpolation along
+// with its position in the file, an escaped `\${` stays in the text as a literal `${`
+fn extract_interpolations<'a>(
+    file: &Rc<Path>,
+    offset: usize,
+    input: &'a str,
+) -> DaisyResult<(String, Vec<(usize, &'a str)>)> {
+    let mut text = String::new();
+    let mut interpolations = vec![];
+    let mut position = 0;
+
+    while let Some(c) = input[position..].chars().next() {
+        if c == '\\' {
+            let escaped = input[position + 1..]
+                .chars()
+                .next()
+                .map_or(0, |c| c.len_utf8());
+            text.push_str(&input[position..position + 1 + escaped]);
+            position += 1 + escaped;
+        } else if input[position..].starts_with("${") {
+            let start = position + 2;
+            let end = closing_brace(&input[start..])
+                .map(|length| start + length)
+                .ok_or_else(|| {
+                    DaisyError::new("Unterminated string interpolation")
+                        .with_location(file, (offset + position, offset + input.len()))
+                })?;
+            interpolations.push((offset + start, &input[start..end]));
+            text.push(PLACEHOLDER);
+            position = end + 1;
+        } else {
+            text.push(c);
+            position += c.len_utf8();
+        }
+    }
+
+    Ok((text, interpolations))
+}
+
+fn closing_brace(input: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escape = false;
+
+    for (i, c) in input.char_indices() {
+        if let Some(delimiter) = quote {
+            if escape {
+                escape = false;
+            } else if c == '\\' {
+                escape = true;
+            } else if c == delimiter {
+                quote = None;
+            }
+            continue;
+        }
+
+        match c {
+            '"' | '`' => quote = Some(c),
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn compile(
+    file: &Rc<Path>,
+    text: &str,
+    interpolations: Vec<(usize, &str)>,
+) -> DaisyResult<Template> {
+    if interpolations.is_empty() {
+        return Ok(Template::Text(text.into()));
+    }
+
+    let mut parts = text.split(PLACEHOLDER);
+    let mut segments = vec![Segment::Text(parts.next().unwrap_or_default().into())];
+    for ((position, source), text) in interpolations.into_iter().zip(parts) {
+        segments.push(Segment::Interpolation(parse_interpolation(
+            file, position, source,
+        )?));
+        segments.push(Segment::Text(text.into()));
+    }
+    Ok(Template::Interpolated(segments))
+}
+
+// the source is padded up to its position so the spans of the parsed expression point into the file
+fn parse_interpolation(file: &Rc<Path>, position: usize, source: &str) -> DaisyResult<Expression> {
+    let padded = format!("{}{}", " ".repeat(position), source);
+    EXPRESSION_PARSER.with(|parser| {
+        parser
+            .parse(file, &padded)
+            .map_err(|err| error::parse_error(file, err))
+    })
 }
 
 fn escape_characters(input: &str) -> String {
@@ -45,3 +175,75 @@ fn escape_characters(input: &str) -> String {
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, rc::Rc};
+
+    use super::extract_interpolations;
+    use crate::ast::environment::Value;
+    use crate::ast::test::run;
+
+    fn interpolations(input: &str) -> (String, Vec<(usize, String)>) {
+        let file: Rc<Path> = Rc::from(Path::new("test.ds"));
+        let (text, interpolations) = extract_interpolations(&file, 0, input).unwrap();
+        let interpolations = interpolations
+            .into_iter()
+            .map(|(position, source)| (position, source.to_string()))
+            .collect();
+        (text, interpolations)
+    }
+
+    fn string(source: &str) -> String {
+        match run(source).unwrap() {
+            Value::String(string) => string,
+            value => panic!("expected a string, got {}", value.get_type()),
+        }
+    }
+
+    #[test]
+    fn extracts_interpolations() {
+        let (text, interpolations) = interpolations(r"a ${b} \${c} ${ {d}.e }");
+        assert_eq!(text, "a \0 \\${c} \0");
+        assert_eq!(
+            interpolations,
+            vec![(4, "b".into()), (15, " {d}.e ".into())]
+        );
+    }
+
+    #[test]
+    fn braces_in_quotes_do_not_close_interpolations() {
+        let (_, interpolations) = interpolations(r#"${m["}"]}"#);
+        assert_eq!(interpolations, vec![(2, r#"m["}"]"#.into())]);
+    }
+
+    #[test]
+    fn interpolates_expressions() {
+        assert_eq!(
+            string(r#"str name = "you" return "hi ${name}!""#),
+            "hi you!"
+        );
+        assert_eq!(string(r#"return "${1 + 2} \${x}""#), "3 ${x}");
+    }
+
+    #[test]
+    fn interpolations_may_contain_strings() {
+        let source = r#"
+            map m = { str a = "x" }
+            return "x ${m["a"]} ${"y" + "z"} ${ { str b = "w" }["b"] }"
+        "#;
+        assert_eq!(string(source), "x x yz w");
+    }
+
+    #[test]
+    fn multiline_strings_interpolate() {
+        let source = "map m = { str a = \"x\" }\nreturn ''\n  a ${m[\"a\"]}\n  ''";
+        assert_eq!(string(source), "a x");
+    }
+
+    #[test]
+    fn unterminated_interpolation_is_an_error() {
+        let err = run(r#"return "a ${b""#).err().unwrap();
+        assert_eq!(err.message, "Unterminated string interpolation");
+    }
+}
@@ -0,0 +1,34 @@
+use std::{path::Path, rc::Rc};
+
+use super::builtin;
+use super::environment::{Scope, Value};
+use super::function::default_function;
+use crate::context::{Config, Context};
+use crate::error::{self, DaisyResult};
+
+// runs a page given as source and returns the value it produced
+pub fn run(source: &str) -> DaisyResult<Value> {
+    let mut ctx = Context::new(Config::default());
+    let (value, _) = run_in(&mut ctx, source)?;
+    Ok(value)
+}
+
+// runs a page given as source and renders the value it produced as html
+pub fn render(source: &str) -> DaisyResult<String> {
+    let mut ctx = Context::new(Config::default());
+    let (value, mut scope) = run_in(&mut ctx, source)?;
+    value.render_html(&mut ctx, &mut scope)
+}
+
+pub fn run_in(ctx: &mut Context, source: &str) -> DaisyResult<(Value, Scope)> {
+    let path: Rc<Path> = Rc::from(Path::new("test.ds"));
+    let (_, ast) = ctx
+        .parser
+        .parse(&path, source)
+        .map_err(|err| error::parse_error(&path, err))?;
+
+    let mut scope = Scope::new();
+    builtin::init(ctx, &mut scope)?;
+    let value = default_function(ctx, &ast, &vec![], &mut scope.clone())?;
+    Ok((value, scope))
+}
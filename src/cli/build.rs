@@ -1,63 +1,280 @@
-use std::path::Path;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
+use super::sitemap;
+use crate::ast::environment::{convert, Scope, Value};
 use crate::ast::function::default_function;
 use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
+use crate::output::{self, feed, Format, XML_DECLARATION};
 use crate::resolver::{self, resource::Resource};
 
-pub fn build(ctx: &mut Context) {
-    resolver::load_dir(ctx);
+pub fn build(ctx: &mut Context) -> Vec<DaisyError> {
+    let mut errors = resolver::load_dir(ctx);
+    errors.extend(resolver::data::load(ctx).err());
 
     // Process pages
-    resolver::get_all(ctx).iter().for_each(|resource| {
-        if let Resource::File(file) = &*resource.borrow() {
-            if !file.is_page {
-                return;
-            }
+    resolver::get_all(ctx)
+        .iter()
+        .for_each(|resource| errors.extend(build_page(ctx, resource).err()));
+    errors.extend(feed::write_feeds(ctx).err());
 
-            let mut scope = file.get_scope(ctx);
+    // after pages have been process, new resources have been added, process these resources
+    resolver::get_all(ctx)
+        .iter()
+        .for_each(|resource| errors.extend(build_resource(ctx, resource).err()));
+    errors.extend(write_manifest(ctx).err());
+    errors.extend(sitemap::write_sitemap(ctx).err());
 
-            let output_path = if let Some(meta) = scope.get("meta") {
-                let meta = meta.clone().try_into_map().unwrap();
-                let url = meta.get("url").unwrap();
-                let url = url.clone().try_into_string().unwrap();
+    report(&errors);
+    errors
+}
 
-                Resource::get_output_path(ctx, url.as_str()).unwrap()
-            } else {
-                Resource::get_output_path(ctx, &file.src.to_str().unwrap()).unwrap()
-            };
+pub fn rebuild(ctx: &mut Context, changed: &HashSet<PathBuf>) -> Vec<DaisyError> {
+    remove_deleted(ctx, changed);
+    resolver::invalidate(ctx, changed);
+    let cached = resolver::get_all(ctx);
+    let is_cached = |resource: &Rc<RefCell<Resource>>| {
+        cached.iter().any(|existing| Rc::ptr_eq(existing, resource))
+    };
 
-            let content = default_function(ctx, &file.ast, &vec![], &mut scope.clone());
-            let content = content.render(ctx, &mut scope);
-            let output = ctx.save_content(output_path.to_str().unwrap(), content.as_str());
-            println!("[DAISY] Built {} -> {}", file.src.to_str().unwrap(), output);
-        } else {
-            panic!("Expected a File resource for page");
-        }
-    });
+    // reloads the changed pages and picks up newly created ones
+    let mut errors = resolver::load_dir(ctx);
+    errors.extend(resolver::data::load(ctx).err());
 
-    // after pages have been process, new resources have been added, process these resources
+    // only pages that are new or (transitively) depend on a changed file need to be rebuilt
+    let pages: Vec<_> = resolver::get_all(ctx)
+        .into_iter()
+        .filter(|resource| match &*resource.borrow() {
+            Resource::File(file) => {
+                file.is_page
+                    && (!is_cached(resource) || ctx.dependencies.depends_on(&file.src, changed))
+            }
+            Resource::Markdown(markdown) => {
+                markdown.is_page
+                    && (!is_cached(resource) || ctx.dependencies.depends_on(&markdown.src, changed))
+            }
+            _ => false,
+        })
+        .collect();
+    pages
+        .iter()
+        .for_each(|resource| errors.extend(build_page(ctx, resource).err()));
+    errors.extend(feed::write_feeds(ctx).err());
+
+    // resources that were invalidated are loaded again while building the pages above
     resolver::get_all(ctx)
         .iter()
-        .for_each(|resource| match &*resource.borrow() {
-            Resource::SCSS(src, path, content) => {
-                let output = ctx.save_content(path, content);
-                println!("[SCSS] Built SCSS {} -> {}", src, output);
-            }
-            Resource::Other(src, output) => {
-                std::fs::create_dir_all(Path::new(output).parent().unwrap()).unwrap_or_else(
-                    |err| {
-                        panic!("Failed to create directory {}: {}", output, err);
-                    },
-                );
-
-                std::fs::copy(src, output).unwrap_or_else(|err| {
-                    panic!(
-                        "Failed to copy resource from {} to {}: {}",
-                        src, output, err
-                    );
-                });
-                println!("[ASSET] Copied {} -> {}", src, output);
+        .filter(|resource| !is_cached(resource))
+        .for_each(|resource| errors.extend(build_resource(ctx, resource).err()));
+    errors.extend(write_manifest(ctx).err());
+    errors.extend(sitemap::write_sitemap(ctx).err());
+
+    report(&errors);
+    errors
+}
+
+fn report(errors: &[DaisyError]) {
+    for error in errors {
+        eprintln!("{}\n", error.report());
+    }
+    if !errors.is_empty() {
+        eprintln!("[DAISY] Build finished with {} error(s)", errors.len());
+    }
+}
+
+fn build_page(ctx: &mut Context, resource: &Rc<RefCell<Resource>>) -> DaisyResult<()> {
+    match &*resource.borrow() {
+        Resource::File(file) if file.is_page => write_page(ctx, &file.src, |ctx| {
+            let scope = file.get_scope(ctx)?;
+            let content = default_function(ctx, &file.ast, &vec![], &mut scope.clone())?;
+            Ok((scope, content))
+        }),
+        Resource::Markdown(markdown) if markdown.is_page => {
+            write_page(ctx, &markdown.src, |ctx| markdown.render_page(ctx))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn write_page<F>(ctx: &mut Context, src: &Path, render: F) -> DaisyResult<()>
+where
+    F: FnOnce(&mut Context) -> DaisyResult<(Scope, Value)>,
+{
+    ctx.dependencies.forget(src);
+    ctx.dependencies.enter(src);
+    let output = render_page(ctx, render);
+    ctx.dependencies.leave();
+
+    let output = output.map_err(|err| err.with_file(src))?;
+    println!("[DAISY] Built {} -> {}", src.to_str().unwrap(), output);
+
+    // a page that moved to another url leaves nothing behind at the old one
+    if let Some(previous) = ctx.outputs.insert(src.to_path_buf(), output.clone()) {
+        if previous != output {
+            remove_output(&previous);
+        }
+    }
+    Ok(())
+}
+
+// removes what was written for sources that no longer exist, a deleted directory takes everything
+// in it along
+fn remove_deleted(ctx: &mut Context, changed: &HashSet<PathBuf>) {
+    let is_deleted = |src: &Path| !src.exists() && changed.iter().any(|path| src.starts_with(path));
+
+    let pages: Vec<PathBuf> = ctx
+        .outputs
+        .keys()
+        .filter(|src| is_deleted(src))
+        .cloned()
+        .collect();
+    for src in pages {
+        if let Some(output) = ctx.outputs.remove(&src) {
+            remove_output(&output);
+        }
+    }
+
+    for resource in resolver::get_all(ctx) {
+        match &*resource.borrow() {
+            Resource::SCSS(src, output, _) | Resource::Other(src, output)
+                if is_deleted(Path::new(src)) =>
+            {
+                remove_output(output)
             }
             _ => {}
-        });
+        }
+    }
+}
+
+fn remove_output(output: &str) {
+    if fs::remove_file(output).is_ok() {
+        println!("[DAISY] Removed {}", output);
+    }
+}
+
+fn render_page<F>(ctx: &mut Context, render: F) -> DaisyResult<String>
+where
+    F: FnOnce(&mut Context) -> DaisyResult<(Scope, Value)>,
+{
+    let (mut scope, content) = render(ctx)?;
+
+    let url = match scope.get("meta") {
+        Some(meta) => {
+            let meta = meta.clone().try_into_map().unwrap();
+            meta.get("url")
+                .cloned()
+                .and_then(|url| url.try_into_string())
+                .ok_or_else(|| DaisyError::new("Expected meta.url to be a string"))?
+        }
+        None => return Err(DaisyError::new("No meta found for page")),
+    };
+    let url = ctx.strip_base_path(&url).to_string();
+    let output_path = Resource::get_output_path(ctx, url.as_str())?;
+
+    let content = match Format::from_url(&url) {
+        Format::Html => render_html(ctx, &content, &mut scope)?,
+        Format::Xml => render_xml(ctx, &content, &mut scope)?,
+        // pages at a json url that return data are written as json, anything else as is
+        Format::Json if matches!(content, Value::Map(_) | Value::Array(_)) => {
+            render_json(ctx, &content, &mut scope)?
+        }
+        Format::Css if ctx.config.minify.enabled && ctx.config.minify.css => {
+            output::minify::minify_css(&content.render(ctx, &mut scope)?)
+        }
+        Format::Json | Format::Css | Format::Text => content.render(ctx, &mut scope)?,
+    };
+    ctx.save_content(output_path.to_str().unwrap(), content.as_str())
+}
+
+fn render_html(ctx: &mut Context, content: &Value, scope: &mut Scope) -> DaisyResult<String> {
+    let mut content = content.render_html(ctx, scope)?;
+    if content.starts_with("<html") {
+        content.insert_str(0, "<!DOCTYPE html>");
+    }
+    if ctx.config.minify.enabled {
+        content = output::minify::minify(&content, &ctx.config.minify);
+    } else if ctx.config.pretty {
+        content = output::pretty::pretty(&content, ctx.config.indent);
+    }
+    Ok(content)
+}
+
+fn render_xml(ctx: &mut Context, content: &Value, scope: &mut Scope) -> DaisyResult<String> {
+    ctx.xml = true;
+    let content = content.render_html(ctx, scope);
+    ctx.xml = false;
+
+    let content = content?;
+    if content.starts_with("<?xml") {
+        Ok(content)
+    } else {
+        Ok(format!("{}{}", XML_DECLARATION, content))
+    }
+}
+
+fn render_json(ctx: &mut Context, content: &Value, scope: &mut Scope) -> DaisyResult<String> {
+    let json = convert::to_json(ctx, content, scope)?;
+    let json = if ctx.config.pretty && !ctx.config.minify.enabled {
+        serde_json::to_string_pretty(&json)
+    } else {
+        serde_json::to_string(&json)
+    };
+    json.map_err(|err| DaisyError::new(format!("Failed to serialize JSON: {}", err)))
+}
+
+// maps the source of every asset to the path it was written to
+fn write_manifest(ctx: &mut Context) -> DaisyResult<()> {
+    let mut manifest = BTreeMap::new();
+    for resource in resolver::get_all(ctx) {
+        let (src, output) = match &*resource.borrow() {
+            Resource::SCSS(src, output, _) | Resource::Other(src, output) => {
+                (src.clone(), output.clone())
+            }
+            _ => continue,
+        };
+
+        let src = Resource::get_relative_path_from_root(ctx, &src)?;
+        let output = Resource::get_relative_path(ctx, &output)?;
+        manifest.insert(src.trim_start_matches('/').to_string(), output);
+    }
+
+    let content = serde_json::to_string_pretty(&manifest)
+        .map_err(|err| DaisyError::new(format!("Failed to serialize manifest: {}", err)))?;
+    let path = format!("{}manifest.json", ctx.get_output_path());
+    ctx.save_content(&path, &content)?;
+    Ok(())
+}
+
+fn build_resource(ctx: &mut Context, resource: &Rc<RefCell<Resource>>) -> DaisyResult<()> {
+    match &*resource.borrow() {
+        Resource::SCSS(src, path, content) => {
+            let output = ctx.save_content(path, content)?;
+            println!("[SCSS] Built SCSS {} -> {}", src, output);
+        }
+        Resource::Generated(path, content) => {
+            let output = ctx.save_content(path, content)?;
+            println!("[DAISY] Generated {}", output);
+        }
+        Resource::Other(src, output) => {
+            std::fs::create_dir_all(Path::new(output).parent().unwrap()).map_err(|err| {
+                DaisyError::new(format!("Failed to create directory {}: {}", output, err))
+            })?;
+
+            std::fs::copy(src, output).map_err(|err| {
+                DaisyError::new(format!(
+                    "Failed to copy resource from {} to {}: {}",
+                    src, output, err
+                ))
+            })?;
+            println!("[ASSET] Copied {} -> {}", src, output);
+        }
+        _ => {}
+    }
+    Ok(())
 }
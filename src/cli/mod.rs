@@ -1,20 +1,108 @@
-use crate::context::Context;
-use clap::Command;
+use std::path::PathBuf;
+
+use crate::context::{Context, Options};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
 
 mod build;
+mod serve;
+mod sitemap;
+mod watch;
 
 fn build_command() -> Command {
     Command::new("daisy")
         .arg_required_else_help(true)
-        .subcommand(Command::new("build").about("Build the site"))
+        .arg(
+            Arg::new("config")
+                .short('c')
+                .long("config")
+                .help("Config file to use instead of the nearest daisy.toml")
+                .global(true)
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("profile")
+                .short('P')
+                .long("profile")
+                .help("Profile from the config to apply, defaults to $DAISY_PROFILE")
+                .global(true),
+        )
+        .arg(
+            Arg::new("workdir")
+                .short('w')
+                .long("workdir")
+                .help("Overrides paths.workdir")
+                .global(true)
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("out")
+                .short('o')
+                .long("out")
+                .help("Overrides paths.output")
+                .global(true)
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .subcommand(
+            Command::new("build").about("Build the site").arg(
+                Arg::new("release")
+                    .long("release")
+                    .help("Minify the output, regardless of the minify config")
+                    .action(ArgAction::SetTrue),
+            ),
+        )
+        .subcommand(Command::new("watch").about("Build the site and rebuild changed pages"))
+        .subcommand(
+            Command::new("serve")
+                .about("Build and serve the site, rebuilding on changes")
+                .arg(
+                    Arg::new("port")
+                        .short('p')
+                        .long("port")
+                        .help("Port to listen on")
+                        .default_value("8080")
+                        .value_parser(value_parser!(u16)),
+                ),
+        )
+}
+
+fn load_options(matches: &ArgMatches) -> Options {
+    Options {
+        config: matches.get_one::<PathBuf>("config").cloned(),
+        profile: matches.get_one::<String>("profile").cloned(),
+        workdir: matches.get_one::<PathBuf>("workdir").cloned(),
+        output: matches.get_one::<PathBuf>("out").cloned(),
+    }
 }
 
-pub fn run(ctx: &mut Context) {
+fn load_context(options: &Options) -> Context {
+    Context::load_config(options).unwrap_or_else(|err| {
+        eprintln!("{}", err.report());
+        std::process::exit(1);
+    })
+}
+
+pub fn run() {
     let matches = build_command().get_matches();
+    let options = load_options(&matches);
+    let mut ctx = load_context(&options);
+    let ctx = &mut ctx;
 
     match matches.subcommand() {
-        Some(("build", _)) => {
+        Some(("build", args)) => {
+            if args.get_flag("release") {
+                ctx.config.minify.enabled = true;
+            }
+            if !build::build(ctx).is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Some(("watch", _)) => {
             build::build(ctx);
+            watch::watch(ctx, &options, || {});
+        }
+        Some(("serve", args)) => {
+            let port = *args.get_one::<u16>("port").unwrap();
+            serve::serve(ctx, &options, port);
         }
         _ => {
             println!("Unknown command");
@@ -0,0 +1,209 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use tiny_http::{Header, Request, Response, Server};
+
+use super::{build, watch};
+use crate::context::{Context, Options};
+
+const RELOAD_ENDPOINT: &str = "/__daisy/version";
+const RELOAD_SCRIPT: &str = r#"<script>
+(() => {
+  let version = null;
+  setInterval(async () => {
+    try {
+      const response = await fetch("/__daisy/version");
+      const current = await response.text();
+      if (version !== null && version !== current) {
+        location.reload();
+      }
+      version = current;
+    } catch (_) {}
+  }, 500);
+})();
+</script>"#;
+
+pub fn serve(ctx: &mut Context, options: &Options, port: u16) {
+    build::build(ctx);
+
+    let version = Arc::new(AtomicUsize::new(0));
+    let output = PathBuf::from(ctx.get_output_path());
+    let base_path = ctx.get_base_path().to_string();
+    let address = format!("127.0.0.1:{}", port);
+    let server = Server::http(&address).unwrap_or_else(|err| {
+        panic!("Failed to start server on {}: {}", address, err);
+    });
+    println!(
+        "[SERVE] Serving {} on http://{}{}/",
+        output.display(),
+        address,
+        base_path
+    );
+
+    let server_version = version.clone();
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            handle_request(request, &output, &base_path, &server_version);
+        }
+    });
+
+    watch::watch(ctx, options, || {
+        version.fetch_add(1, Ordering::SeqCst);
+    });
+}
+
+fn handle_request(request: Request, output: &Path, base_path: &str, version: &AtomicUsize) {
+    let url = request
+        .url()
+        .split(['?', '#'])
+        .next()
+        .unwrap_or("/")
+        .to_string();
+    // urls that can't be decoded don't match any file
+    let url = percent_decode(&url).unwrap_or_default();
+
+    let result = if url == RELOAD_ENDPOINT {
+        let body = version.load(Ordering::SeqCst).to_string();
+        request.respond(
+            Response::from_string(body).with_header(content_type("text/plain; charset=utf-8")),
+        )
+    } else if let Some(path) = url
+        .strip_prefix(base_path)
+        .filter(|url| url.is_empty() || url.starts_with('/'))
+        .and_then(|url| resolve_path(output, url))
+    {
+        match fs::read(&path) {
+            Ok(mut body) => {
+                let mime = mime_type(&path);
+                if mime.starts_with("text/html") {
+                    inject_reload_script(&mut body);
+                }
+                request.respond(Response::from_data(body).with_header(content_type(mime)))
+            }
+            Err(_) => request.respond(not_found()),
+        }
+    } else {
+        request.respond(not_found())
+    };
+
+    if let Err(err) = result {
+        println!("[SERVE] Failed to respond to {}: {}", url, err);
+    }
+}
+
+// decodes escapes like `%20` in a url path, the decoded path has to be valid utf-8
+fn percent_decode(url: &str) -> Option<String> {
+    let bytes = url.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut position = 0;
+    while position < bytes.len() {
+        if bytes[position] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(position + 1..position + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            position += 3;
+        } else {
+            decoded.push(bytes[position]);
+            position += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn resolve_path(output: &Path, url: &str) -> Option<PathBuf> {
+    let relative = Path::new(url.trim_start_matches('/'));
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return None;
+    }
+
+    let path = output.join(relative);
+    if path.is_dir() {
+        Some(path.join("index.html")).filter(|index| index.is_file())
+    } else if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+fn inject_reload_script(body: &mut Vec<u8>) {
+    let html = String::from_utf8_lossy(body);
+    let injected = match html.rfind("</body>") {
+        Some(index) => format!("{}{}{}", &html[..index], RELOAD_SCRIPT, &html[index..]),
+        None => format!("{}{}", html, RELOAD_SCRIPT),
+    };
+    *body = injected.into_bytes();
+}
+
+fn not_found() -> Response<std::io::Cursor<Vec<u8>>> {
+    Response::from_string("404 - Not found")
+        .with_status_code(404)
+        .with_header(content_type("text/plain; charset=utf-8"))
+}
+
+fn content_type(mime: &str) -> Header {
+    Header::from_bytes("Content-Type", mime).unwrap()
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("webmanifest") => "application/manifest+json",
+        Some("xml") => "application/xml",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::{percent_decode, resolve_path};
+
+    #[test]
+    fn decodes_urls() {
+        assert_eq!(percent_decode("/a%20b/caf%C3%A9").unwrap(), "/a b/café");
+        assert_eq!(percent_decode("/plain").unwrap(), "/plain");
+        assert!(percent_decode("/bad%2").is_none());
+        assert!(percent_decode("/bad%zz").is_none());
+        assert!(percent_decode("/bad%ff").is_none());
+    }
+
+    #[test]
+    fn resolves_encoded_paths() {
+        let output = env::temp_dir().join(format!("daisy-serve-{}", std::process::id()));
+        fs::create_dir_all(output.join("my posts")).unwrap();
+        fs::write(output.join("my posts/index.html"), "").unwrap();
+        fs::write(output.join("café.png"), "").unwrap();
+
+        let resolve = |url: &str| resolve_path(&output, &percent_decode(url).unwrap());
+        assert_eq!(
+            resolve("/my%20posts/"),
+            Some(output.join("my posts/index.html"))
+        );
+        assert_eq!(resolve("/caf%C3%A9.png"), Some(output.join("café.png")));
+        assert_eq!(resolve("/%2e%2e/etc/passwd"), None);
+        fs::remove_dir_all(output).unwrap();
+    }
+}
@@ -0,0 +1,64 @@
+use crate::ast::{environment::Value, html::escape};
+use crate::context::Context;
+use crate::error::DaisyResult;
+use crate::output::{Format, XML_DECLARATION};
+use crate::resolver;
+
+// writes sitemap.xml with every html page that doesn't set `meta.sitemap = false`, and a
+// robots.txt pointing to it
+pub fn write_sitemap(ctx: &mut Context) -> DaisyResult<()> {
+    let robots = match &ctx.config.sitemap {
+        Some(sitemap) => sitemap.robots,
+        None => return Ok(()),
+    };
+
+    let mut entries = vec![];
+    for (_, meta) in resolver::get_pages(ctx)? {
+        let url = match meta.get("url") {
+            Some(Value::String(url)) => url.clone(),
+            _ => continue,
+        };
+        if matches!(meta.get("sitemap"), Some(Value::Bool(false)))
+            || Format::from_url(&url) != Format::Html
+        {
+            continue;
+        }
+
+        let path = ctx.strip_base_path(&url).to_string();
+        let mut entry = format!(
+            "<url><loc>{}</loc>",
+            escape(ctx, &ctx.get_absolute_url(&path))
+        );
+        if let Some(lastmod) = meta.get("lastmod").cloned() {
+            let lastmod = lastmod.render(ctx, &mut meta.clone())?;
+            entry.push_str(&format!("<lastmod>{}</lastmod>", escape(ctx, &lastmod)));
+        }
+        if let Some(priority) = meta.get("priority").cloned() {
+            let priority = priority.render(ctx, &mut meta.clone())?;
+            entry.push_str(&format!("<priority>{}</priority>", escape(ctx, &priority)));
+        }
+        entry.push_str("</url>");
+        entries.push(entry);
+    }
+    entries.sort();
+
+    let sitemap = format!(
+        "{}\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n{}\n</urlset>\n",
+        XML_DECLARATION,
+        entries.join("\n")
+    );
+    let path = format!("{}sitemap.xml", ctx.get_output_path());
+    let output = ctx.save_content(&path, &sitemap)?;
+    println!("[DAISY] Built sitemap -> {}", output);
+
+    if robots {
+        let robots = format!(
+            "User-agent: *\nAllow: /\n\nSitemap: {}\n",
+            ctx.get_absolute_url("/sitemap.xml")
+        );
+        let path = format!("{}robots.txt", ctx.get_output_path());
+        let output = ctx.save_content(&path, &robots)?;
+        println!("[DAISY] Built robots.txt -> {}", output);
+    }
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::build;
+use crate::context::{Context, Options};
+use crate::resolver::resource::Resource;
+
+// the directories that are watched, recursively or not
+#[derive(Default)]
+struct Watched {
+    recursive: HashSet<PathBuf>,
+    directories: HashSet<PathBuf>,
+}
+
+pub fn watch<F: FnMut()>(ctx: &mut Context, options: &Options, mut on_rebuild: F) {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).unwrap_or_else(|err| {
+        panic!("Failed to create file watcher: {}", err);
+    });
+    let mut watched = Watched::default();
+    watch_paths(ctx, &mut watcher, &mut watched);
+    println!("[WATCH] Watching {}", ctx.config.paths.workdir);
+
+    while let Ok(event) = rx.recv() {
+        let output = Path::new(&ctx.config.paths.workdir).join(&ctx.config.paths.output);
+        let mut changed = HashSet::new();
+        collect_changes(event, &output, &mut changed);
+
+        // editors tend to emit several events per save, wait for them to settle
+        while let Ok(event) = rx.recv_timeout(Duration::from_millis(100)) {
+            collect_changes(event, &output, &mut changed);
+        }
+
+        if changed.is_empty() {
+            continue;
+        }
+
+        for path in &changed {
+            println!("[WATCH] Changed {}", path.display());
+        }
+        if changed.contains(&ctx.config.src) {
+            // any part of the site can depend on the config, it is loaded again and everything rebuilt
+            match Context::load_config(options) {
+                Ok(reloaded) => {
+                    *ctx = reloaded;
+                    build::build(ctx);
+                }
+                Err(err) => eprintln!("{}\n", err.report()),
+            }
+        } else {
+            build::rebuild(ctx, &changed);
+        }
+        watch_paths(ctx, &mut watcher, &mut watched);
+        on_rebuild();
+    }
+}
+
+// the pages, assets and data directories and the config, along with the directories of every other
+// file pages use, like components, which can be anywhere in the workdir
+fn watch_paths(ctx: &Context, watcher: &mut RecommendedWatcher, watched: &mut Watched) {
+    let workdir = Path::new(&ctx.config.paths.workdir);
+    for path in [
+        workdir.join(&ctx.config.paths.pages),
+        workdir.join(&ctx.config.assets.folder),
+        ctx.get_data_path(),
+    ] {
+        if path.is_dir() && !watched.recursive.contains(&path) {
+            match watcher.watch(&path, RecursiveMode::Recursive) {
+                Ok(()) => {
+                    watched.recursive.insert(path);
+                }
+                Err(err) => println!("[WATCH] Failed to watch {}: {}", path.display(), err),
+            }
+        }
+    }
+
+    // files are replaced rather than written to by some editors, so their directory is watched
+    let mut directories = vec![ctx.config.src.parent().map(Path::to_path_buf)];
+    directories.extend(
+        ctx.resources
+            .iter()
+            .map(|resource| match &*resource.borrow() {
+                Resource::Generated(_, _) => None,
+                resource => resource.get_src().parent().map(Path::to_path_buf),
+            }),
+    );
+    for directory in directories.into_iter().flatten() {
+        let is_watched = watched.directories.contains(&directory)
+            || watched
+                .recursive
+                .iter()
+                .any(|path| directory.starts_with(path));
+        if is_watched || !directory.is_dir() {
+            continue;
+        }
+
+        match watcher.watch(&directory, RecursiveMode::NonRecursive) {
+            Ok(()) => {
+                watched.directories.insert(directory);
+            }
+            Err(err) => println!("[WATCH] Failed to watch {}: {}", directory.display(), err),
+        }
+    }
+}
+
+fn collect_changes(
+    event: notify::Result<notify::Event>,
+    output: &Path,
+    changed: &mut HashSet<PathBuf>,
+) {
+    match event {
+        Ok(event) => {
+            if event.kind.is_access() || event.kind.is_other() {
+                return;
+            }
+            changed.extend(
+                event
+                    .paths
+                    .into_iter()
+                    .filter(|path| !path.starts_with(output) && !path.is_dir()),
+            );
+        }
+        Err(err) => println!("[WATCH] Watch error: {}", err),
+    }
+}
@@ -1,35 +1,145 @@
-use std::{cell::RefCell, fs, path::Path, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
-use crate::{grammar::DaisyParser, resolver::resource::Resource};
+use crate::{
+    ast::environment::Value,
+    error::{DaisyError, DaisyResult},
+    grammar::DaisyParser,
+    resolver::{dependencies::Dependencies, resource::Resource},
+};
 use log::warn;
 use serde::Deserialize;
 
 pub struct Context {
     pub parser: DaisyParser,
     pub resources: Vec<Rc<RefCell<Resource>>>,
+    pub dependencies: Dependencies,
     pub config: Config,
+
+    // the contents of the data directory, see `resolver::data::get_data`
+    pub data: Option<Value>,
+    // the file every page was last written to, removed again once the page is deleted
+    pub outputs: HashMap<PathBuf, String>,
+
+    // set while rendering the contents of elements like script and style which are not escaped
+    pub raw_text: bool,
+    // set while rendering xml pages, elements are written the xml way instead of the html way
+    pub xml: bool,
+}
+
+// overrides given on the command line, these take precedence over the config file
+pub struct Options {
+    pub config: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub workdir: Option<PathBuf>,
+    pub output: Option<PathBuf>,
 }
 
 #[derive(Deserialize)]
-#[serde(default)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
     pub paths: Paths,
     pub assets: Assets,
     pub pretty: bool,
+    pub indent: usize,
+    pub minify: Minify,
+    pub markdown: Markdown,
+    pub base_url: String,
+    pub sitemap: Option<Sitemap>,
+    pub feed: Vec<Feed>,
+    pub profile: BTreeMap<String, toml::Table>,
+    #[serde(alias = "params")]
+    pub site: toml::Table,
+
+    // the config after the active profile was applied, exposed to pages as `config`
+    #[serde(skip)]
+    pub table: toml::Table,
+    // the file the config was loaded from, watched for changes
+    #[serde(skip)]
+    pub src: PathBuf,
 }
 
 #[derive(Deserialize)]
-#[serde(default)]
+#[serde(default, deny_unknown_fields)]
 pub struct Paths {
     pub workdir: String,
     pub pages: String,
     pub output: String,
+    pub data: String,
 }
 
 #[derive(Deserialize)]
-#[serde(default)]
+#[serde(default, deny_unknown_fields)]
 pub struct Assets {
     pub folder: String,
+    pub use_uuids: bool,
+    pub fingerprint: Option<Fingerprint>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Fingerprint {
+    None,
+    Hash,
+    Uuid,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Minify {
+    pub enabled: bool,
+    pub html: bool,
+    pub css: bool,
+    pub js: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Markdown {
+    // the component markdown pages are rendered through unless their front matter sets a layout
+    pub layout: Option<String>,
+}
+
+// writing a sitemap is opt-in, adding a `[sitemap]` table to the config turns it on
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Sitemap {
+    pub robots: bool,
+}
+
+impl Default for Sitemap {
+    fn default() -> Self {
+        Sitemap { robots: true }
+    }
+}
+
+// a `[[feed]]` table in the config, also the options taken by `std.feed`
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Feed {
+    pub format: FeedFormat,
+    pub url: Option<String>,
+    // only pages with a url starting with this are part of the feed
+    pub prefix: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub limit: Option<usize>,
+    // includes the rendered page in every entry instead of only the summary
+    pub content: bool,
+}
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    #[default]
+    Rss,
+    Atom,
 }
 
 impl Default for Config {
@@ -38,6 +148,16 @@ impl Default for Config {
             paths: Paths::default(),
             assets: Assets::default(),
             pretty: false,
+            indent: 2,
+            minify: Minify::default(),
+            markdown: Markdown::default(),
+            base_url: "".to_string(),
+            sitemap: None,
+            feed: vec![],
+            profile: BTreeMap::new(),
+            site: toml::Table::new(),
+            table: toml::Table::new(),
+            src: PathBuf::new(),
         }
     }
 }
@@ -48,6 +168,7 @@ impl Default for Paths {
             workdir: ".".to_string(),
             pages: "src".to_string(),
             output: "site".to_string(),
+            data: "data".to_string(),
         }
     }
 }
@@ -56,56 +177,301 @@ impl Default for Assets {
     fn default() -> Self {
         Assets {
             folder: "assets".to_string(),
+            use_uuids: false,
+            fingerprint: None,
+        }
+    }
+}
+
+impl Assets {
+    // `use_uuids` is the older way of picking uuids, an explicit `fingerprint` takes precedence
+    pub fn fingerprint(&self) -> Fingerprint {
+        match self.fingerprint {
+            Some(fingerprint) => fingerprint,
+            None if self.use_uuids => Fingerprint::Uuid,
+            None => Fingerprint::Hash,
+        }
+    }
+}
+
+impl Default for Minify {
+    fn default() -> Self {
+        Minify {
+            enabled: false,
+            html: true,
+            css: true,
+            js: true,
         }
     }
 }
 
 const DAISY_CONFIG: &str = "daisy.toml";
+const DAISY_PROFILE: &str = "DAISY_PROFILE";
+
+impl Config {
+    fn validate(&self, src: &Path) -> DaisyResult<()> {
+        let workdir = Path::new(&self.paths.workdir);
+        let pages = workdir.join(&self.paths.pages);
+        if !pages.is_dir() {
+            return Err(DaisyError::new(format!(
+                "Pages directory {} does not exist",
+                pages.display()
+            ))
+            .with_file(src));
+        }
+
+        // sitemaps and feeds only allow absolute urls
+        if self.sitemap.is_some() && !self.base_url.contains("://") {
+            return Err(DaisyError::new(
+                "Invalid config: [sitemap] needs base_url to be the address the site is served at, e.g. https://example.com/",
+            )
+            .with_file(src));
+        }
+        if !self.feed.is_empty() && !self.base_url.contains("://") {
+            return Err(DaisyError::new(
+                "Invalid config: [[feed]] needs base_url to be the address the site is served at, e.g. https://example.com/",
+            )
+            .with_file(src));
+        }
+
+        let assets = workdir.join(&self.assets.folder);
+        if !assets.is_dir() {
+            warn!("Assets directory {} does not exist", assets.display());
+        }
+        Ok(())
+    }
+}
+
+fn suggest<'a>(unknown: &str, names: &[&'a str]) -> Option<&'a str> {
+    names
+        .iter()
+        .map(|name| (strsim::levenshtein(unknown, name), *name))
+        .filter(|(distance, _)| *distance <= 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+// tables are merged key by key, any other value in the overlay replaces the one in the base
+fn merge(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => merge(base, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+// urls with a scheme, protocol relative urls and fragments don't point to a path on this site
+fn is_external(url: &str) -> bool {
+    url.starts_with("//")
+        || url.starts_with('#')
+        || url
+            .split('/')
+            .next()
+            .is_some_and(|first| first.contains(':'))
+}
+
+// looks for the closest config file, starting at the current directory and walking up its parents
+fn find_config() -> Option<PathBuf> {
+    let current = env::current_dir().ok()?;
+    current
+        .ancestors()
+        .map(|dir| dir.join(DAISY_CONFIG))
+        .find(|config| config.is_file())
+}
+
+// turns serde's unknown field and variant errors into a suggestion for the closest known name
+fn config_error(src: &Path, err: toml::de::Error) -> DaisyError {
+    let message = err.message();
+    let names: Vec<&str> = message.split('`').skip(1).step_by(2).collect();
+    let message = match names.split_first() {
+        Some((unknown, expected))
+            if message.starts_with("unknown field") || message.starts_with("unknown variant") =>
+        {
+            match suggest(unknown, expected) {
+                Some(name) => format!("{}, did you mean `{}`?", message, name),
+                None => message.to_string(),
+            }
+        }
+        _ => message.to_string(),
+    };
+
+    let error = DaisyError::new(format!("Invalid config: {}", message));
+    match err.span() {
+        Some(span) => error.with_location(src, (span.start, span.end)),
+        None => error.with_file(src),
+    }
+}
 
 impl Context {
-    pub fn load_config() -> Self {
-        let config_str = std::fs::read_to_string(DAISY_CONFIG).unwrap_or_else(|_| {
-            warn!("{} not found, using default config", DAISY_CONFIG);
-            "".to_string()
-        });
+    pub fn load_config(options: &Options) -> DaisyResult<Self> {
+        let src = match &options.config {
+            Some(config) if !config.is_file() => {
+                return Err(DaisyError::new(format!(
+                    "Config file {} does not exist",
+                    config.display()
+                )));
+            }
+            Some(config) => Some(config.clone()),
+            None => find_config(),
+        };
+
+        let config_str = match &src {
+            Some(src) => fs::read_to_string(src).map_err(|err| {
+                DaisyError::new(format!("Failed to read config: {}", err)).with_file(src)
+            })?,
+            None => {
+                warn!("{} not found, using default config", DAISY_CONFIG);
+                "".to_string()
+            }
+        };
+        let src = src.unwrap_or_else(|| PathBuf::from(DAISY_CONFIG));
+
+        let mut cfg: Config = toml::from_str(&config_str).map_err(|err| config_error(&src, err))?;
+        let mut table: toml::Table =
+            toml::from_str(&config_str).map_err(|err| config_error(&src, err))?;
+        table.remove("profile");
 
-        let mut cfg: Config = toml::from_str(&config_str).unwrap();
+        let profile = options
+            .profile
+            .clone()
+            .or_else(|| env::var(DAISY_PROFILE).ok())
+            .filter(|profile| !profile.is_empty());
+        if let Some(profile) = profile {
+            let overlay = cfg.profile.remove(&profile).ok_or_else(|| {
+                let profiles: Vec<&str> = cfg.profile.keys().map(|name| name.as_str()).collect();
+                let message = format!("Unknown profile `{}`", profile);
+                let message = match suggest(&profile, &profiles) {
+                    Some(name) => format!("{}, did you mean `{}`?", message, name),
+                    None => message,
+                };
+                DaisyError::new(message).with_file(&src)
+            })?;
+
+            merge(&mut table, overlay);
+            cfg = Config::deserialize(toml::Value::Table(table.clone()))
+                .map_err(|err| config_error(&src, err))?;
+            table.insert("profile".into(), toml::Value::String(profile));
+        }
+        cfg.table = table;
+        cfg.src = std::path::absolute(&src)?;
 
-        let absolute_src = std::fs::canonicalize(&cfg.paths.workdir).unwrap_or_else(|_| {
-            panic!("{} not found, using default src", cfg.paths.workdir);
-        });
+        // paths in the config are relative to the config file, paths on the command line to the
+        // directory daisy is run from
+        let workdir = match &options.workdir {
+            Some(workdir) => workdir.clone(),
+            None => src
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join(&cfg.paths.workdir),
+        };
+        let workdir = if workdir.as_os_str().is_empty() {
+            PathBuf::from(".")
+        } else {
+            workdir
+        };
+
+        let absolute_src = fs::canonicalize(&workdir).map_err(|err| {
+            DaisyError::new(format!(
+                "Work directory {} could not be opened: {}",
+                workdir.display(),
+                err
+            ))
+            .with_file(&src)
+        })?;
         cfg.paths.workdir = absolute_src.to_str().unwrap().to_string();
 
+        if let Some(output) = &options.output {
+            cfg.paths.output = std::path::absolute(output)?.to_str().unwrap().to_string();
+        }
+        cfg.validate(&src)?;
+
+        Ok(Context::new(cfg))
+    }
+
+    pub fn new(config: Config) -> Self {
         Context {
             parser: DaisyParser::new(),
             resources: vec![],
-            config: cfg,
+            dependencies: Dependencies::default(),
+            config,
+            data: None,
+            outputs: HashMap::new(),
+            raw_text: false,
+            xml: false,
         }
     }
 
     pub fn get_output_path(&self) -> String {
-        format!(
-            "{}/{}/",
-            self.config.paths.workdir, self.config.paths.output
-        )
+        // an absolute output directory replaces the workdir when joined
+        let output = Path::new(&self.config.paths.workdir).join(&self.config.paths.output);
+        format!("{}/", output.to_str().unwrap())
+    }
+
+    // the path part of base_url without a trailing slash, e.g. `/blog` for `https://example.com/blog/`
+    pub fn get_base_path(&self) -> &str {
+        let base_url = self.config.base_url.as_str();
+        let path = match base_url.split_once("://") {
+            Some((_, rest)) => rest.find('/').map_or("", |i| &rest[i..]),
+            None => base_url,
+        };
+        path.trim_end_matches('/')
+    }
+
+    // prefixes a path on this site with the base path, urls pointing elsewhere are left alone
+    pub fn get_url(&self, path: &str) -> String {
+        if is_external(path) {
+            return path.to_string();
+        }
+        format!("{}/{}", self.get_base_path(), path.trim_start_matches('/'))
+    }
+
+    pub fn get_absolute_url(&self, path: &str) -> String {
+        if is_external(path) {
+            return path.to_string();
+        }
+        let url = self.get_url(path);
+        let origin = match self.config.base_url.split_once("://") {
+            Some((scheme, rest)) => {
+                let host = rest.split('/').next().unwrap_or(rest);
+                format!("{}://{}", scheme, host)
+            }
+            None => "".to_string(),
+        };
+        format!("{}{}", origin, url)
+    }
+
+    // the inverse of get_url, turns a url on this site back into a path relative to the output
+    pub fn strip_base_path<'a>(&self, url: &'a str) -> &'a str {
+        let base = self.get_base_path();
+        match url.strip_prefix(base) {
+            Some(rest) if !base.is_empty() && (rest.is_empty() || rest.starts_with('/')) => rest,
+            _ => url,
+        }
+    }
+
+    pub fn get_data_path(&self) -> PathBuf {
+        Path::new(&self.config.paths.workdir).join(&self.config.paths.data)
     }
 
     pub fn get_page_path(&self) -> String {
         format!("{}/{}/", self.config.paths.workdir, self.config.paths.pages)
     }
 
-    pub fn save_content(&self, path: &str, content: &str) -> String {
+    pub fn save_content(&self, path: &str, content: &str) -> DaisyResult<String> {
         let output_path = Path::new(path);
 
-        fs::create_dir_all(output_path.parent().unwrap()).unwrap_or_else(|err| {
-            panic!("Failed to create directory: {}: {}", path, err);
-        });
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).map_err(|err| {
+                DaisyError::new(format!("Failed to create directory: {}: {}", path, err))
+            })?;
+        }
 
-        fs::write(&output_path, content).unwrap_or_else(|err| {
-            println!("{}", content);
-            panic!("Failed to write file: {}: {}", path, err);
-        });
+        fs::write(&output_path, content)
+            .map_err(|err| DaisyError::new(format!("Failed to write file: {}: {}", path, err)))?;
 
-        output_path.to_str().unwrap().to_string()
+        Ok(output_path.to_str().unwrap().to_string())
     }
 }
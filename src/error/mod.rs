@@ -0,0 +1,160 @@
+use lalrpop_util::ParseError;
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::grammar::Token;
+
+pub type DaisyResult<T> = Result<T, DaisyError>;
+
+#[derive(Debug, Clone)]
+pub struct DaisyError {
+    pub file: Option<PathBuf>,
+    pub span: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl DaisyError {
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        DaisyError {
+            file: None,
+            span: None,
+            message: message.into(),
+        }
+    }
+
+    // errors keep the innermost location they were raised at, outer callers only fill in the gaps
+    pub fn with_file(mut self, file: &Path) -> Self {
+        if self.file.is_none() {
+            self.file = Some(file.to_path_buf());
+        }
+        self
+    }
+
+    // an error that already names another file, like a page listed in a feed, stays with that file
+    pub fn with_location(mut self, file: &Path, span: (usize, usize)) -> Self {
+        let same_file = self
+            .file
+            .as_deref()
+            .map_or(true, |existing| existing == file);
+        if self.span.is_none() && same_file {
+            self.file = Some(file.to_path_buf());
+            self.span = Some(span);
+        }
+        self
+    }
+
+    pub fn report(&self) -> String {
+        match (&self.file, self.span) {
+            (Some(file), Some((start, end))) => {
+                let content = fs::read_to_string(file).unwrap_or_default();
+                let (line, column) = position_to_line_column(&content, start);
+                format!(
+                    "error: {}\n{}--> {}:{}:{}\n{}",
+                    self.message,
+                    " ".repeat(line.to_string().len()),
+                    file.display(),
+                    line,
+                    column,
+                    snippet(&content, start, end)
+                )
+            }
+            (Some(file), None) => format!("error: {}\n --> {}", self.message, file.display()),
+            (None, _) => format!("error: {}", self.message),
+        }
+    }
+}
+
+// renders the line a span starts on with the spanned part underlined, spans running over
+// multiple lines are underlined up until the end of their first line
+fn snippet(content: &str, start: usize, end: usize) -> String {
+    let start = start.min(content.len());
+    let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = content[start..]
+        .find('\n')
+        .map_or(content.len(), |i| start + i);
+    let source_line = content[line_start..line_end].trim_end_matches('\r');
+    let (line, _) = position_to_line_column(content, start);
+
+    let prefix: String = content[line_start..start]
+        .chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let underline = content[start..end.clamp(start, line_end)]
+        .chars()
+        .count()
+        .max(1);
+
+    let gutter = " ".repeat(line.to_string().len());
+    format!(
+        "{} |\n{} | {}\n{} | {}{}",
+        gutter,
+        line,
+        source_line,
+        gutter,
+        prefix,
+        "^".repeat(underline)
+    )
+}
+
+impl From<std::io::Error> for DaisyError {
+    fn from(err: std::io::Error) -> Self {
+        DaisyError::new(err.to_string())
+    }
+}
+
+impl Display for DaisyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{} in file {}", self.message, file.display()),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+pub fn parse_error(src: &Path, err: ParseError<usize, Token, DaisyError>) -> DaisyError {
+    let (span, message) = match err {
+        ParseError::InvalidToken { location } => ((location, location), "Invalid token".into()),
+        ParseError::UnrecognizedEof { location, expected } => (
+            (location, location),
+            format!("Unrecognized EOF. Expected: {}", expected.join(", ")),
+        ),
+        ParseError::UnrecognizedToken {
+            token: (start, token, end),
+            expected,
+        } => (
+            (start, end),
+            format!(
+                "Unrecognized token '{}'. Expected: {}",
+                token,
+                expected.join(", ")
+            ),
+        ),
+        ParseError::ExtraToken {
+            token: (start, token, end),
+        } => ((start, end), format!("Extra token '{}'", token)),
+        ParseError::User { error } => return error.with_file(src),
+    };
+
+    DaisyError::new(message).with_location(src, span)
+}
+
+pub fn position_to_line_column(input: &str, pos: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut last_line_start = 0;
+
+    for (i, c) in input.char_indices() {
+        if i >= pos {
+            break;
+        }
+        if c == '\n' {
+            line += 1;
+            last_line_start = i + 1;
+        }
+    }
+
+    let column = pos - last_line_start + 1;
+    (line, column)
+}
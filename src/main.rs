@@ -4,6 +4,8 @@ use lalrpop_util::lalrpop_mod;
 mod ast;
 mod cli;
 mod context;
+mod error;
+mod output;
 mod resolver;
 
 lalrpop_mod!(grammar);
@@ -11,7 +13,6 @@ lalrpop_mod!(grammar);
 fn main() {
     let env = Env::default().filter_or("DAISY_LOG", "trace");
     env_logger::init_from_env(env);
-    let mut ctx = context::Context::load_config();
 
-    cli::run(&mut ctx);
+    cli::run();
 }
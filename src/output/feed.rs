@@ -0,0 +1,555 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+
+use crate::ast::environment::{Scope, Value};
+use crate::ast::function::default_function;
+use crate::ast::html::escape;
+use crate::context::{Context, Feed, FeedFormat};
+use crate::error::{DaisyError, DaisyResult};
+use crate::resolver::{self, file::File, resource::Resource};
+
+use super::{Format, XML_DECLARATION};
+
+struct Entry {
+    title: String,
+    url: String,
+    date: Option<Date>,
+    summary: Option<String>,
+    content: Option<String>,
+    author: Option<String>,
+}
+
+// writes the feeds from the config, every html page below the prefix of a feed is part of it
+pub fn write_feeds(ctx: &mut Context) -> DaisyResult<()> {
+    for feed in ctx.config.feed.clone() {
+        let pages = resolver::get_pages(ctx)?
+            .into_iter()
+            .map(|(_, meta)| meta)
+            .filter(|meta| match meta.get("url") {
+                Some(Value::String(url)) => Format::from_url(url) == Format::Html,
+                _ => false,
+            })
+            .collect();
+        write_feed(ctx, &feed, pages)?;
+    }
+    Ok(())
+}
+
+// adds the feed for the given page metas as a generated resource and returns the url it is written to
+pub fn write_feed(ctx: &mut Context, feed: &Feed, pages: Vec<Scope>) -> DaisyResult<String> {
+    // feeds only allow absolute urls
+    if !ctx.config.base_url.contains("://") {
+        return Err(DaisyError::new(
+            "Feeds need base_url to be the address the site is served at, e.g. https://example.com/",
+        ));
+    }
+
+    // the page a meta belongs to, for errors and the full content
+    let sources: HashMap<String, PathBuf> = resolver::get_pages(ctx)?
+        .into_iter()
+        .filter_map(|(src, meta)| match meta.get("url") {
+            Some(Value::String(url)) => Some((url.clone(), src)),
+            _ => None,
+        })
+        .collect();
+
+    let mut entries = vec![];
+    for mut meta in pages {
+        let url = match meta.get("url") {
+            Some(Value::String(url)) => ctx.strip_base_path(url).to_string(),
+            _ => return Err(DaisyError::new("Expected meta.url to be a string")),
+        };
+        if url.starts_with(&feed.prefix) {
+            entries.push(entry(ctx, feed, &mut meta, &sources)?);
+        }
+    }
+
+    // newest first, entries without a date go last
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.date.map(|date| date.timestamp())));
+    if let Some(limit) = feed.limit {
+        entries.truncate(limit);
+    }
+
+    let url = match (&feed.url, feed.format) {
+        (Some(url), _) => ctx.strip_base_path(url).to_string(),
+        (None, FeedFormat::Rss) => "/feed.xml".to_string(),
+        (None, FeedFormat::Atom) => "/atom.xml".to_string(),
+    };
+    let content = match feed.format {
+        FeedFormat::Rss => rss(ctx, feed, &url, &entries),
+        FeedFormat::Atom => atom(ctx, feed, &url, &entries),
+    };
+    resolver::generate(ctx, &url, content)
+}
+
+fn entry(
+    ctx: &mut Context,
+    feed: &Feed,
+    meta: &mut Scope,
+    sources: &HashMap<String, PathBuf>,
+) -> DaisyResult<Entry> {
+    let url = match meta.get("url") {
+        Some(Value::String(url)) => url.clone(),
+        _ => return Err(DaisyError::new("Expected meta.url to be a string")),
+    };
+    let src = sources.get(&url);
+
+    let date = match field(ctx, meta, "date")? {
+        Some(date) => Some(date),
+        None => field(ctx, meta, "lastmod")?,
+    };
+    let date = match date {
+        Some(date) => Some(Date::parse(&date).ok_or_else(|| {
+            let error = DaisyError::new(format!(
+                "Invalid date `{}` for {}, expected a date like 2024-01-31 or 2024-01-31T12:00:00Z",
+                date, url
+            ));
+            match src {
+                Some(src) => error.with_file(src),
+                None => error,
+            }
+        })?),
+        None => None,
+    };
+
+    let summary = match field(ctx, meta, "summary")? {
+        Some(summary) => Some(summary),
+        None => field(ctx, meta, "description")?,
+    };
+    // only html pages have content that can be embedded, others like xml or json pages are listed
+    // without it
+    let path = ctx.strip_base_path(&url).to_string();
+    let content = match src {
+        Some(src) if feed.content && Format::from_url(&path) == Format::Html => {
+            render_content(ctx, src)?
+        }
+        _ => None,
+    };
+
+    Ok(Entry {
+        title: field(ctx, meta, "title")?.unwrap_or_else(|| url.clone()),
+        url: ctx.get_absolute_url(&path),
+        date,
+        summary,
+        content,
+        author: field(ctx, meta, "author")?,
+    })
+}
+
+fn field(ctx: &mut Context, meta: &mut Scope, key: &str) -> DaisyResult<Option<String>> {
+    match meta.get(key).cloned() {
+        None | Some(Value::Nil) => Ok(None),
+        Some(value) => Ok(Some(value.render(ctx, meta)?)),
+    }
+}
+
+// markdown pages are included without their layout, any other page as it is rendered. pages that
+// are being rendered already, like the page writing the feed, are left without content as
+// rendering them again would never end
+fn render_content(ctx: &mut Context, src: &Path) -> DaisyResult<Option<String>> {
+    let resource = resolver::get_file(ctx, src.to_str().unwrap().to_string())?;
+    let content = match &*resource.borrow() {
+        Resource::Markdown(markdown) => Some(markdown.html.clone()),
+        Resource::File(file) if !ctx.dependencies.is_entered(&file.src) => {
+            ctx.dependencies.enter(&file.src);
+            let content = render_file(ctx, file);
+            ctx.dependencies.leave();
+            Some(content.map_err(|err| err.with_file(&file.src))?)
+        }
+        _ => None,
+    };
+    Ok(content)
+}
+
+fn render_file(ctx: &mut Context, file: &File) -> DaisyResult<String> {
+    let mut scope = file.get_scope(ctx)?;
+    let content = default_function(ctx, &file.ast, &vec![], &mut scope.clone())?;
+    content.render_html(ctx, &mut scope)
+}
+
+fn site(ctx: &Context, key: &str) -> Option<String> {
+    ctx.config
+        .site
+        .get(key)
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+fn rss(ctx: &Context, feed: &Feed, url: &str, entries: &[Entry]) -> String {
+    let title = feed.title.clone().or_else(|| site(ctx, "title"));
+    let title = title.unwrap_or_default();
+    let description = feed
+        .description
+        .clone()
+        .or_else(|| site(ctx, "description"));
+    let description = description.unwrap_or_else(|| title.clone());
+
+    let mut output = format!(
+        "{}\n<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n<channel>\n",
+        XML_DECLARATION
+    );
+    output.push_str(&format!("<title>{}</title>\n", escape(ctx, &title)));
+    output.push_str(&format!(
+        "<link>{}</link>\n",
+        escape(ctx, &ctx.get_absolute_url("/"))
+    ));
+    output.push_str(&format!(
+        "<description>{}</description>\n",
+        escape(ctx, &description)
+    ));
+    output.push_str(&format!(
+        "<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        escape(ctx, &ctx.get_absolute_url(url))
+    ));
+    if let Some(date) = entries.iter().find_map(|entry| entry.date) {
+        output.push_str(&format!(
+            "<lastBuildDate>{}</lastBuildDate>\n",
+            date.rfc822()
+        ));
+    }
+
+    for entry in entries {
+        output.push_str("<item>\n");
+        output.push_str(&format!("<title>{}</title>\n", escape(ctx, &entry.title)));
+        output.push_str(&format!("<link>{}</link>\n", escape(ctx, &entry.url)));
+        output.push_str(&format!(
+            "<guid isPermaLink=\"true\">{}</guid>\n",
+            escape(ctx, &entry.url)
+        ));
+        if let Some(date) = entry.date {
+            output.push_str(&format!("<pubDate>{}</pubDate>\n", date.rfc822()));
+        }
+        if let Some(author) = entry.author.clone().or_else(|| feed.author.clone()) {
+            output.push_str(&format!(
+                "<dc:creator>{}</dc:creator>\n",
+                escape(ctx, &author)
+            ));
+        }
+        if let Some(summary) = &entry.summary {
+            output.push_str(&format!(
+                "<description>{}</description>\n",
+                escape(ctx, summary)
+            ));
+        }
+        if let Some(content) = &entry.content {
+            output.push_str(&format!(
+                "<content:encoded>{}</content:encoded>\n",
+                escape(ctx, content)
+            ));
+        }
+        output.push_str("</item>\n");
+    }
+
+    output.push_str("</channel>\n</rss>\n");
+    output
+}
+
+fn atom(ctx: &Context, feed: &Feed, url: &str, entries: &[Entry]) -> String {
+    let title = feed.title.clone().or_else(|| site(ctx, "title"));
+    let description = feed
+        .description
+        .clone()
+        .or_else(|| site(ctx, "description"));
+    let author = feed.author.clone().or_else(|| site(ctx, "author"));
+    // atom needs a date for every entry, entries without one use the date of the newest entry
+    let updated = entries
+        .iter()
+        .find_map(|entry| entry.date)
+        .unwrap_or_else(Date::now);
+
+    let mut output = format!(
+        "{}\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n",
+        XML_DECLARATION
+    );
+    output.push_str(&format!(
+        "<title>{}</title>\n",
+        escape(ctx, &title.unwrap_or_default())
+    ));
+    if let Some(description) = description {
+        output.push_str(&format!(
+            "<subtitle>{}</subtitle>\n",
+            escape(ctx, &description)
+        ));
+    }
+    output.push_str(&format!(
+        "<link href=\"{}\"/>\n",
+        escape(ctx, &ctx.get_absolute_url("/"))
+    ));
+    output.push_str(&format!(
+        "<link href=\"{}\" rel=\"self\" type=\"application/atom+xml\"/>\n",
+        escape(ctx, &ctx.get_absolute_url(url))
+    ));
+    output.push_str(&format!(
+        "<id>{}</id>\n",
+        escape(ctx, &ctx.get_absolute_url(url))
+    ));
+    output.push_str(&format!("<updated>{}</updated>\n", updated.rfc3339()));
+    if let Some(author) = author {
+        output.push_str(&format!(
+            "<author><name>{}</name></author>\n",
+            escape(ctx, &author)
+        ));
+    }
+
+    for entry in entries {
+        output.push_str("<entry>\n");
+        output.push_str(&format!("<title>{}</title>\n", escape(ctx, &entry.title)));
+        output.push_str(&format!("<link href=\"{}\"/>\n", escape(ctx, &entry.url)));
+        output.push_str(&format!("<id>{}</id>\n", escape(ctx, &entry.url)));
+        output.push_str(&format!(
+            "<updated>{}</updated>\n",
+            entry.date.unwrap_or(updated).rfc3339()
+        ));
+        if let Some(author) = &entry.author {
+            output.push_str(&format!(
+                "<author><name>{}</name></author>\n",
+                escape(ctx, author)
+            ));
+        }
+        if let Some(summary) = &entry.summary {
+            output.push_str(&format!("<summary>{}</summary>\n", escape(ctx, summary)));
+        }
+        if let Some(content) = &entry.content {
+            output.push_str(&format!(
+                "<content type=\"html\">{}</content>\n",
+                escape(ctx, content)
+            ));
+        }
+        output.push_str("</entry>\n");
+    }
+
+    output.push_str("</feed>\n");
+    output
+}
+
+#[derive(Clone, Copy)]
+struct Date(DateTime<FixedOffset>);
+
+impl Date {
+    // dates like 2024-01-31, optionally followed by a time like T12:00:00 and an offset like Z,
+    // +02:00 or +0200, which is what toml dates and most front matter look like. dates copied from
+    // another feed in the rfc 2822 format work as well
+    fn parse(input: &str) -> Option<Date> {
+        let input = input.trim();
+        if let Ok(date) = DateTime::parse_from_rfc3339(input) {
+            return Some(Date(date));
+        }
+        if let Ok(date) = DateTime::parse_from_rfc2822(input) {
+            return Some(Date(date));
+        }
+        for format in ["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%d %H:%M:%S%.f%z"] {
+            if let Ok(date) = DateTime::parse_from_str(input, format) {
+                return Some(Date(date));
+            }
+        }
+
+        // without an offset the date is in utc
+        let naive = [
+            "%Y-%m-%dT%H:%M:%S%.f",
+            "%Y-%m-%d %H:%M:%S%.f",
+            "%Y-%m-%dT%H:%M",
+            "%Y-%m-%d %H:%M",
+        ]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+        Some(Date(naive.and_utc().fixed_offset()))
+    }
+
+    fn now() -> Date {
+        Date(DateTime::<Utc>::from(SystemTime::now()).fixed_offset())
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.0.timestamp()
+    }
+
+    fn rfc3339(&self) -> String {
+        self.0.to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    fn rfc822(&self) -> String {
+        self.0.format("%a, %d %b %Y %H:%M:%S %z").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use super::{write_feed, write_feeds, Date};
+    use crate::context::{Config, Context, Feed};
+    use crate::resolver::{self, resource::Resource};
+
+    // a site in a temporary directory with the given pages
+    fn site(name: &str, pages: &[(&str, &str)]) -> (Context, PathBuf) {
+        let workdir = env::temp_dir().join(format!("daisy-feed-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&workdir);
+        for (path, content) in pages {
+            let path = workdir.join("src").join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut config = Config::default();
+        config.paths.workdir = workdir.to_str().unwrap().to_string();
+        config.base_url = "https://example.com/".to_string();
+        config.feed = vec![Feed {
+            content: true,
+            ..Feed::default()
+        }];
+        let mut ctx = Context::new(config);
+        assert!(resolver::load_dir(&mut ctx).is_empty());
+        (ctx, workdir)
+    }
+
+    fn generated(ctx: &Context, url: &str) -> String {
+        ctx.resources
+            .iter()
+            .find_map(|resource| match &*resource.borrow() {
+                Resource::Generated(output, content) if output.ends_with(url) => {
+                    Some(content.clone())
+                }
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_dates() {
+        let date = Date::parse("2024-05-01").unwrap();
+        assert_eq!(date.rfc3339(), "2024-05-01T00:00:00Z");
+        assert_eq!(date.rfc822(), "Wed, 01 May 2024 00:00:00 +0000");
+
+        let date = Date::parse("2024-03-05T10:30:15.25+02:00").unwrap();
+        assert_eq!(date.rfc3339(), "2024-03-05T10:30:15+02:00");
+        assert_eq!(date.rfc822(), "Tue, 05 Mar 2024 10:30:15 +0200");
+        assert_eq!(date.timestamp(), 1709627415);
+
+        let date = Date::parse("1969-12-31 23:00:00-01:30").unwrap();
+        assert_eq!(date.rfc3339(), "1969-12-31T23:00:00-01:30");
+        assert_eq!(date.timestamp(), 5400 - 3600);
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        for input in [
+            "yesterday",
+            "2024-13-01",
+            "2024-01-32",
+            "2024-01-01-01",
+            "2024-01-01T25:00",
+            "2024-01-01T12:00+02",
+        ] {
+            assert!(Date::parse(input).is_none(), "{} should be invalid", input);
+        }
+    }
+
+    #[test]
+    fn parses_other_offsets_and_formats() {
+        let date = Date::parse("2024-03-05T10:30:00+0200").unwrap();
+        assert_eq!(date.rfc3339(), "2024-03-05T10:30:00+02:00");
+        let date = Date::parse("2024-03-05T10:30").unwrap();
+        assert_eq!(date.rfc3339(), "2024-03-05T10:30:00Z");
+        let date = Date::parse("Tue, 05 Mar 2024 10:30:00 +0200").unwrap();
+        assert_eq!(date.rfc3339(), "2024-03-05T10:30:00+02:00");
+    }
+
+    #[test]
+    fn rejects_days_past_the_end_of_the_month() {
+        assert!(Date::parse("2024-02-29").is_some());
+        for input in [
+            "2024-02-30",
+            "2024-02-31",
+            "2023-02-29",
+            "2024-04-31T12:00:00Z",
+        ] {
+            assert!(Date::parse(input).is_none(), "{} should be invalid", input);
+        }
+    }
+
+    #[test]
+    fn pages_writing_a_feed_of_themselves_do_not_recurse() {
+        let page = r#"
+            meta {
+                str url = "/feeds"
+                str title = "Feeds"
+            }
+            str feed = std.feed(std.pages(), {
+                str url = "/own.xml"
+                bool content = true
+            })
+            return : html { body { p > "Feeds" } }
+        "#;
+        let (mut ctx, workdir) = site("recursion", &[("feeds.ds", page)]);
+        write_feeds(&mut ctx).unwrap();
+
+        assert!(generated(&ctx, "/feed.xml").contains("&lt;p&gt;Feeds&lt;/p&gt;"));
+        let own = generated(&ctx, "/own.xml");
+        assert!(own.contains("<title>Feeds</title>"));
+        assert!(!own.contains("<content:encoded>"));
+        fs::remove_dir_all(workdir).unwrap();
+    }
+
+    #[test]
+    fn only_html_pages_have_content() {
+        let html = r#"
+            meta { str title = "Html" }
+            return : html { body { p > "Html" } }
+        "#;
+        let xml = r#"
+            meta {
+                str url = "/data.xml"
+                str title = "Xml"
+            }
+            return : data { item > "x" }
+        "#;
+        let (mut ctx, workdir) = site("html", &[("html.ds", html), ("xml.ds", xml)]);
+        let pages = resolver::get_pages(&mut ctx)
+            .unwrap()
+            .into_iter()
+            .map(|(_, meta)| meta)
+            .collect();
+        let feed = Feed {
+            content: true,
+            ..Feed::default()
+        };
+        write_feed(&mut ctx, &feed, pages).unwrap();
+
+        let feed = generated(&ctx, "/feed.xml");
+        assert!(feed.contains("<title>Xml</title>"));
+        assert!(feed.contains("&lt;p&gt;Html&lt;/p&gt;"));
+        assert!(!feed.contains("&lt;item&gt;"));
+        fs::remove_dir_all(workdir).unwrap();
+    }
+
+    #[test]
+    fn invalid_dates_point_to_their_page() {
+        for date in ["yesterday", "2024-02-31"] {
+            let page = format!("+++\ndate = \"{}\"\n+++\nHello", date);
+            let (mut ctx, workdir) = site("dates", &[("post.md", &page)]);
+            let err = write_feeds(&mut ctx).unwrap_err();
+
+            assert!(err.message.starts_with(&format!("Invalid date `{}`", date)));
+            assert_eq!(err.file, Some(workdir.join("src/post.md")));
+            fs::remove_dir_all(workdir).unwrap();
+        }
+    }
+
+    #[test]
+    fn feeds_need_an_absolute_base_url() {
+        let (mut ctx, workdir) = site("base-url", &[("post.md", "Hello")]);
+        ctx.config.base_url = "/blog/".to_string();
+        assert!(write_feeds(&mut ctx).is_err());
+        fs::remove_dir_all(workdir).unwrap();
+    }
+}
@@ -0,0 +1,199 @@
+use log::warn;
+
+use super::{tokenize, Token, INLINE};
+use crate::context::Minify;
+
+pub fn minify(html: &str, config: &Minify) -> String {
+    let tokens = tokenize(html);
+    let mut output = String::with_capacity(html.len());
+    let mut element = "";
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            // conditional comments are the only ones that still mean something to browsers
+            Token::Markup(markup)
+                if config.html && markup.starts_with("<!--") && !markup.starts_with("<!--[") => {}
+            Token::Markup(markup) => output.push_str(markup),
+            Token::Start(name, source) => {
+                element = name;
+                if config.html {
+                    output.push_str(&minify_tag(source));
+                } else {
+                    output.push_str(source);
+                }
+            }
+            Token::End(_, source) => output.push_str(source),
+            Token::Text(text) if config.html => {
+                let text = collapse_whitespace(text);
+                let text = if is_inline(i.checked_sub(1).and_then(|i| tokens.get(i))) {
+                    text.as_str()
+                } else {
+                    text.trim_start()
+                };
+                let text = if is_inline(tokens.get(i + 1)) {
+                    text
+                } else {
+                    text.trim_end()
+                };
+                output.push_str(text);
+            }
+            Token::Text(text) => output.push_str(text),
+            Token::Verbatim(content) => {
+                let source = match i.checked_sub(1).and_then(|i| tokens.get(i)) {
+                    Some(Token::Start(_, source)) => *source,
+                    _ => "",
+                };
+                match element {
+                    "style" if config.css => output.push_str(&minify_css(content)),
+                    "script" if config.js && is_javascript(source) => {
+                        output.push_str(&minify_js(content))
+                    }
+                    _ => output.push_str(content),
+                }
+            }
+        }
+    }
+
+    output
+}
+
+pub fn minify_css(css: &str) -> String {
+    let options = grass::Options::default().style(grass::OutputStyle::Compressed);
+    match grass::from_string(css, &options) {
+        Ok(minified) => minified.trim_end().to_string(),
+        Err(err) => {
+            warn!("Failed to minify inline style, keeping it as is: {}", err);
+            css.to_string()
+        }
+    }
+}
+
+pub fn minify_js(js: &str) -> String {
+    match minifier::js::minify(js) {
+        Ok(minified) => minified.to_string(),
+        Err(err) => {
+            warn!("Failed to minify inline script, keeping it as is: {}", err);
+            js.to_string()
+        }
+    }
+}
+
+// whitespace next to text or inline elements is rendered, next to anything else it can go
+fn is_inline(token: Option<&Token>) -> bool {
+    match token {
+        Some(Token::Text(_)) => true,
+        Some(Token::Start(name, _)) | Some(Token::End(name, _)) => INLINE.contains(&name.as_str()),
+        _ => false,
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut whitespace = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !whitespace {
+                output.push(' ');
+            }
+            whitespace = true;
+        } else {
+            output.push(c);
+            whitespace = false;
+        }
+    }
+    output
+}
+
+fn is_javascript(source: &str) -> bool {
+    match attributes(source)
+        .into_iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("type"))
+    {
+        Some((_, Some((value, _)))) => {
+            let value = value.to_lowercase();
+            value.is_empty() || value.contains("javascript") || value == "module"
+        }
+        _ => true,
+    }
+}
+
+// rewrites a start tag with single spaces between the attributes and without the quotes that
+// aren't needed
+fn minify_tag(source: &str) -> String {
+    if !source.ends_with('>') || source.ends_with("/>") {
+        return source.to_string();
+    }
+
+    let name: String = source[1..]
+        .chars()
+        .take_while(|c| !c.is_whitespace() && *c != '>')
+        .collect();
+    let mut output = format!("<{}", name);
+    for (name, value) in attributes(source) {
+        match value {
+            Some((value, _)) if can_unquote(value) => {
+                output.push_str(&format!(" {}={}", name, value))
+            }
+            Some((value, quote)) => {
+                output.push_str(&format!(" {}={}{}{}", name, quote, value, quote))
+            }
+            None => output.push_str(&format!(" {}", name)),
+        }
+    }
+    output.push('>');
+    output
+}
+
+fn can_unquote(value: &str) -> bool {
+    !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_ascii_whitespace() || "\"'=<>`".contains(c))
+}
+
+// the attributes of a start tag with their value and the quote that surrounded it
+fn attributes(source: &str) -> Vec<(&str, Option<(&str, char)>)> {
+    let inner = source
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .trim_end_matches('/');
+    let mut rest = inner.trim_start_matches(|c: char| !c.is_whitespace());
+    let mut attributes = vec![];
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let name = &rest[..end];
+        rest = rest[end..].trim_start();
+
+        if let Some(value) = rest.strip_prefix('=') {
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'');
+            let (content, remaining, quote) = match quote {
+                Some(quote) => {
+                    let end = value[1..].find(quote).map_or(value.len(), |end| end + 1);
+                    (&value[1..end], value.get(end + 1..).unwrap_or(""), quote)
+                }
+                None => {
+                    let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                    (&value[..end], &value[end..], '"')
+                }
+            };
+            attributes.push((name, Some((content, quote))));
+            rest = remaining;
+        } else {
+            if name.is_empty() {
+                break;
+            }
+            attributes.push((name, None));
+        }
+    }
+
+    attributes
+}
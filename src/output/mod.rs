@@ -0,0 +1,149 @@
+pub mod feed;
+pub mod minify;
+pub mod pretty;
+
+// the contents of these elements are taken over as is, whitespace in them is significant or they
+// are not html at all
+const VERBATIM: [&str; 4] = ["pre", "textarea", "script", "style"];
+
+// elements that are part of the text around them, whitespace next to them is significant
+const INLINE: [&str; 28] = [
+    "a", "abbr", "b", "bdi", "bdo", "br", "button", "cite", "code", "data", "dfn", "em", "i",
+    "img", "input", "kbd", "label", "mark", "q", "s", "samp", "small", "span", "strong", "sub",
+    "sup", "time", "u",
+];
+
+pub const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+// what a page is written as, picked by the extension of its url
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Html,
+    Xml,
+    Json,
+    Css,
+    Text,
+}
+
+impl Format {
+    pub fn from_url(url: &str) -> Format {
+        let name = url.rsplit('/').next().unwrap_or(url);
+        match name.rsplit_once('.').map(|(_, extension)| extension) {
+            Some("xml") => Format::Xml,
+            Some("json") | Some("webmanifest") => Format::Json,
+            Some("css") => Format::Css,
+            Some("txt") => Format::Text,
+            _ => Format::Html,
+        }
+    }
+}
+
+pub enum Token<'a> {
+    // doctypes, comments and anything else that is passed through untouched
+    Markup(&'a str),
+    Start(String, &'a str),
+    End(String, &'a str),
+    Text(&'a str),
+    Verbatim(&'a str),
+}
+
+pub fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut position = 0;
+
+    while position < html.len() {
+        let rest = &html[position..];
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            position += end;
+            continue;
+        }
+
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(rest.len(), |end| end + 3);
+            tokens.push(Token::Markup(&rest[..end]));
+            position += end;
+            continue;
+        }
+
+        let end = tag_end(rest);
+        let source = &rest[..end];
+        position += end;
+
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            tokens.push(Token::Markup(source));
+        } else if let Some(name) = rest.strip_prefix("</") {
+            tokens.push(Token::End(tag_name(name), source));
+        } else {
+            let name = tag_name(&rest[1..]);
+            if name.is_empty() {
+                tokens.push(Token::Text(source));
+                continue;
+            }
+
+            let verbatim = VERBATIM.contains(&name.as_str());
+            tokens.push(Token::Start(name.clone(), source));
+            if verbatim {
+                let rest = &html[position..];
+                let end = find_closing_tag(rest, &name).unwrap_or(rest.len());
+                if end > 0 {
+                    tokens.push(Token::Verbatim(&rest[..end]));
+                }
+                position += end;
+            }
+        }
+    }
+
+    tokens
+}
+
+pub fn is_void(name: &str) -> bool {
+    crate::ast::html::VOID.contains(&name)
+}
+
+// finds the `>` closing a tag, skipping over quoted attribute values
+fn tag_end(input: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    input.len()
+}
+
+fn tag_name(input: &str) -> String {
+    input
+        .chars()
+        .take_while(|c| !c.is_whitespace() && *c != '/' && *c != '>')
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn find_closing_tag(input: &str, name: &str) -> Option<usize> {
+    let lowercase = input.to_lowercase();
+    let pattern = format!("</{}", name);
+    lowercase.find(&pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Format;
+
+    #[test]
+    fn formats_follow_the_extension_of_the_url() {
+        assert!(Format::from_url("/blog/post") == Format::Html);
+        assert!(Format::from_url("/index.html") == Format::Html);
+        assert!(Format::from_url("/v1.2/notes") == Format::Html);
+        assert!(Format::from_url("/feed.xml") == Format::Xml);
+        assert!(Format::from_url("/search.json") == Format::Json);
+        assert!(Format::from_url("/site.webmanifest") == Format::Json);
+        assert!(Format::from_url("/extra.css") == Format::Css);
+        assert!(Format::from_url("/robots.txt") == Format::Text);
+    }
+}
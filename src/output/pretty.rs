@@ -0,0 +1,145 @@
+use super::{is_void, tokenize, Token, INLINE};
+
+enum Tree<'a> {
+    Element {
+        name: String,
+        start: &'a str,
+        end: Option<&'a str>,
+        children: Vec<Tree<'a>>,
+    },
+    Markup(&'a str),
+    Text(&'a str),
+    Verbatim(&'a str),
+}
+
+pub fn pretty(html: &str, indent: usize) -> String {
+    let mut output = String::new();
+    for tree in parse(tokenize(html)) {
+        print(&tree, 0, indent, &mut output);
+    }
+    output
+}
+
+fn parse(tokens: Vec<Token<'_>>) -> Vec<Tree<'_>> {
+    // the open elements, the bottom of the stack collects the top level nodes
+    let mut stack: Vec<(String, &str, Vec<Tree>)> = vec![(String::new(), "", vec![])];
+
+    for token in tokens {
+        match token {
+            Token::Start(name, start) if is_void(&name) || start.ends_with("/>") => {
+                stack.last_mut().unwrap().2.push(Tree::Element {
+                    name,
+                    start,
+                    end: None,
+                    children: vec![],
+                });
+            }
+            Token::Start(name, start) => stack.push((name, start, vec![])),
+            Token::End(name, end) => {
+                match stack.iter().skip(1).rposition(|(open, ..)| *open == name) {
+                    Some(index) => {
+                        // elements that were left open are closed along with their parent
+                        while stack.len() > index + 1 {
+                            let (name, start, children) = stack.pop().unwrap();
+                            let end = if stack.len() == index + 1 {
+                                Some(end)
+                            } else {
+                                None
+                            };
+                            stack.last_mut().unwrap().2.push(Tree::Element {
+                                name,
+                                start,
+                                end,
+                                children,
+                            });
+                        }
+                    }
+                    None => stack.last_mut().unwrap().2.push(Tree::Markup(end)),
+                }
+            }
+            Token::Markup(markup) => stack.last_mut().unwrap().2.push(Tree::Markup(markup)),
+            Token::Text(text) => stack.last_mut().unwrap().2.push(Tree::Text(text)),
+            Token::Verbatim(text) => stack.last_mut().unwrap().2.push(Tree::Verbatim(text)),
+        }
+    }
+
+    while stack.len() > 1 {
+        let (name, start, children) = stack.pop().unwrap();
+        stack.last_mut().unwrap().2.push(Tree::Element {
+            name,
+            start,
+            end: None,
+            children,
+        });
+    }
+    stack.pop().unwrap().2
+}
+
+fn is_inline(tree: &Tree) -> bool {
+    match tree {
+        Tree::Text(_) => true,
+        Tree::Element { name, children, .. } => {
+            INLINE.contains(&name.as_str()) && children.iter().all(is_inline)
+        }
+        _ => false,
+    }
+}
+
+fn print(tree: &Tree, depth: usize, indent: usize, output: &mut String) {
+    let padding = " ".repeat(depth * indent);
+    match tree {
+        Tree::Element {
+            start,
+            end,
+            children,
+            ..
+        } => {
+            let verbatim = children
+                .iter()
+                .any(|child| matches!(child, Tree::Verbatim(_)));
+
+            if verbatim || children.iter().all(is_inline) {
+                output.push_str(&padding);
+                print_inline(tree, output);
+                output.push('\n');
+                return;
+            }
+
+            output.push_str(&format!("{}{}\n", padding, start));
+            for child in children {
+                print(child, depth + 1, indent, output);
+            }
+            if let Some(end) = end {
+                output.push_str(&format!("{}{}\n", padding, end));
+            }
+        }
+        Tree::Text(text) => {
+            let text = text.trim();
+            if !text.is_empty() {
+                output.push_str(&format!("{}{}\n", padding, text));
+            }
+        }
+        Tree::Markup(markup) => output.push_str(&format!("{}{}\n", padding, markup)),
+        Tree::Verbatim(text) => output.push_str(text),
+    }
+}
+
+fn print_inline(tree: &Tree, output: &mut String) {
+    match tree {
+        Tree::Element {
+            start,
+            end,
+            children,
+            ..
+        } => {
+            output.push_str(start);
+            for child in children {
+                print_inline(child, output);
+            }
+            if let Some(end) = end {
+                output.push_str(end);
+            }
+        }
+        Tree::Markup(text) | Tree::Text(text) | Tree::Verbatim(text) => output.push_str(text),
+    }
+}
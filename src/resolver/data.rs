@@ -0,0 +1,177 @@
+use std::{fs, path::Path};
+
+use crate::ast::environment::{convert, Scope, Value};
+use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
+
+use super::{get_file, resource::Resource};
+
+const EXTENSIONS: [&str; 5] = ["json", "toml", "yaml", "yml", "csv"];
+
+pub fn is_data(src: &Path) -> bool {
+    src.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| EXTENSIONS.contains(&ext))
+}
+
+// the contents of the data directory, loaded once and shared by every page until a file in it changes.
+// pages only depend on the data directory once they read `data`, see `record`
+pub fn get_data(ctx: &mut Context) -> DaisyResult<Value> {
+    load(ctx)?;
+    Ok(ctx.data.clone().unwrap_or(Value::Nil))
+}
+
+pub fn record(ctx: &mut Context) {
+    let data_path = ctx.get_data_path();
+    ctx.dependencies.record(&data_path);
+}
+
+// builds load the data directory before any page so an error in it is reported once, pages get an
+// empty map instead
+pub fn load(ctx: &mut Context) -> DaisyResult<()> {
+    if ctx.data.is_some() {
+        return Ok(());
+    }
+
+    let data = load_dir(ctx, &ctx.get_data_path());
+    match data {
+        Ok(data) => {
+            ctx.data = Some(data);
+            Ok(())
+        }
+        Err(err) => {
+            ctx.data = Some(Value::Map(Scope::new()));
+            Err(err)
+        }
+    }
+}
+
+pub fn load_absolute(src: &Path) -> DaisyResult<Value> {
+    let content = fs::read_to_string(src).map_err(|err| {
+        DaisyError::new(format!("Failed to read data file: {}", err)).with_file(src)
+    })?;
+
+    match src.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => {
+            let value: serde_json::Value = serde_json::from_str(&content).map_err(|err| {
+                let index = offset(&content, err.line(), err.column());
+                DaisyError::new(format!("Invalid JSON: {}", err)).with_location(src, (index, index))
+            })?;
+            Ok(convert::from_json(&value))
+        }
+        Some("toml") => {
+            let table: toml::Table = toml::from_str(&content).map_err(|err| {
+                let error = DaisyError::new(format!("Invalid TOML: {}", err.message()));
+                match err.span() {
+                    Some(span) => error.with_location(src, (span.start, span.end)),
+                    None => error.with_file(src),
+                }
+            })?;
+            Ok(convert::from_toml_table(&table))
+        }
+        Some("yaml") | Some("yml") => {
+            let value: serde_yaml::Value = serde_yaml::from_str(&content).map_err(|err| {
+                let error = DaisyError::new(format!("Invalid YAML: {}", err));
+                match err.location() {
+                    Some(location) => {
+                        error.with_location(src, (location.index(), location.index()))
+                    }
+                    None => error.with_file(src),
+                }
+            })?;
+            Ok(convert::from_yaml(&value))
+        }
+        Some("csv") => {
+            let mut reader = csv::Reader::from_reader(content.as_bytes());
+            let headers = reader.headers().map_err(|err| csv_error(src, err))?.clone();
+            let records = reader
+                .records()
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| csv_error(src, err))?;
+            Ok(convert::from_csv(&headers, &records))
+        }
+        _ => Err(DaisyError::new("Unsupported data file").with_file(src)),
+    }
+}
+
+// the files in the data directory as a map, nested directories become nested maps and files are
+// keyed by their name without the extension
+pub fn load_dir(ctx: &mut Context, dir: &Path) -> DaisyResult<Value> {
+    let mut map = Scope::new();
+    if !dir.is_dir() {
+        return Ok(Value::Map(map));
+    }
+
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    for path in entries {
+        let name = path.file_stem().unwrap().to_str().unwrap().to_string();
+        if path.is_dir() {
+            map.map_insert(name, load_dir(ctx, &path)?);
+        } else if is_data(&path) {
+            let resource = get_file(ctx, path.to_str().unwrap().to_string())?;
+            let value = match &*resource.borrow() {
+                Resource::Data(_, value) => value.clone(),
+                _ => continue,
+            };
+            map.map_insert(name, value);
+        }
+    }
+
+    Ok(Value::Map(map))
+}
+
+fn csv_error(src: &Path, err: csv::Error) -> DaisyError {
+    let error = DaisyError::new(format!("Invalid CSV: {}", err));
+    match err.position() {
+        Some(position) => {
+            let index = position.byte() as usize;
+            error.with_location(src, (index, index))
+        }
+        None => error.with_file(src),
+    }
+}
+
+// serde_json reports lines and columns starting at 1, errors need the byte offset
+fn offset(content: &str, line: usize, column: usize) -> usize {
+    let start: usize = content
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(|line| line.len())
+        .sum();
+    (start + column.saturating_sub(1)).min(content.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, path::Path};
+
+    use crate::ast::test::run_in;
+    use crate::context::{Config, Context};
+
+    fn depends_on_data(source: &str) -> bool {
+        let mut ctx = Context::new(Config::default());
+        let page = Path::new("page.ds");
+        ctx.dependencies.enter(page);
+        run_in(&mut ctx, source).unwrap();
+        ctx.dependencies.leave();
+
+        let changed = HashSet::from([ctx.get_data_path().join("authors.json")]);
+        ctx.dependencies.depends_on(page, &changed)
+    }
+
+    #[test]
+    fn reading_data_records_a_dependency() {
+        assert!(depends_on_data("return data"));
+        assert!(depends_on_data("return data.authors"));
+    }
+
+    #[test]
+    fn pages_without_data_do_not_depend_on_it() {
+        assert!(!depends_on_data("return 1"));
+        assert!(!depends_on_data(r#"str data = "mine" return data"#));
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+#[derive(Default)]
+pub struct Dependencies {
+    edges: HashMap<PathBuf, HashSet<PathBuf>>,
+    stack: Vec<PathBuf>,
+}
+
+impl Dependencies {
+    // marks `src` as the file currently being evaluated, every resource requested until `leave`
+    // is recorded as a dependency of it
+    pub fn enter(&mut self, src: &Path) {
+        self.stack.push(src.to_path_buf());
+    }
+
+    pub fn leave(&mut self) {
+        self.stack.pop();
+    }
+
+    // whether `src` is being evaluated, either directly or further up the stack
+    pub fn is_entered(&self, src: &Path) -> bool {
+        self.stack.iter().any(|entered| entered == src)
+    }
+
+    pub fn record(&mut self, dependency: &Path) {
+        if let Some(current) = self.stack.last() {
+            if current != dependency {
+                self.edges
+                    .entry(current.clone())
+                    .or_default()
+                    .insert(dependency.to_path_buf());
+            }
+        }
+    }
+
+    pub fn forget(&mut self, src: &Path) {
+        self.edges.remove(src);
+    }
+
+    // a dependency on a directory covers every file in it
+    pub fn depends_on(&self, src: &Path, changed: &HashSet<PathBuf>) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending = vec![src];
+
+        while let Some(current) = pending.pop() {
+            if changed.iter().any(|path| path.starts_with(current)) {
+                return true;
+            }
+            if !visited.insert(current) {
+                continue;
+            }
+            if let Some(dependencies) = self.edges.get(current) {
+                pending.extend(dependencies.iter().map(|dep| dep.as_path()));
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        path::{Path, PathBuf},
+    };
+
+    use super::Dependencies;
+
+    fn changed(path: &str) -> HashSet<PathBuf> {
+        HashSet::from([PathBuf::from(path)])
+    }
+
+    #[test]
+    fn changes_reach_pages_transitively() {
+        let mut dependencies = Dependencies::default();
+        dependencies.enter(Path::new("src/index.ds"));
+        dependencies.record(Path::new("component/title.ds"));
+        dependencies.enter(Path::new("component/title.ds"));
+        dependencies.record(Path::new("assets/main.scss"));
+        dependencies.leave();
+        dependencies.leave();
+
+        let index = Path::new("src/index.ds");
+        assert!(dependencies.depends_on(index, &changed("src/index.ds")));
+        assert!(dependencies.depends_on(index, &changed("assets/main.scss")));
+        assert!(!dependencies.depends_on(index, &changed("component/other.ds")));
+        assert!(!dependencies.depends_on(Path::new("src/about.ds"), &changed("assets/main.scss")));
+    }
+
+    #[test]
+    fn directories_cover_their_files() {
+        let mut dependencies = Dependencies::default();
+        dependencies.enter(Path::new("src/blog.ds"));
+        dependencies.record(Path::new("src"));
+        dependencies.leave();
+
+        let blog = Path::new("src/blog.ds");
+        assert!(dependencies.depends_on(blog, &changed("src/posts/new.md")));
+        assert!(!dependencies.depends_on(blog, &changed("srcs/new.md")));
+    }
+
+    #[test]
+    fn tracks_entered_files() {
+        let mut dependencies = Dependencies::default();
+        dependencies.enter(Path::new("src/feed.ds"));
+        dependencies.enter(Path::new("src/post.ds"));
+        assert!(dependencies.is_entered(Path::new("src/feed.ds")));
+        dependencies.leave();
+        dependencies.leave();
+        assert!(!dependencies.is_entered(Path::new("src/feed.ds")));
+    }
+}
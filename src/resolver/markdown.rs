@@ -0,0 +1,145 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use pulldown_cmark::{html, Options, Parser};
+
+use crate::ast::builtin::builtin_use;
+use crate::ast::environment::{convert, Scope, Value};
+use crate::ast::expression::Expression;
+use crate::ast::function::call_function;
+use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
+
+use super::file::page_scope;
+
+pub struct Markdown {
+    pub src: PathBuf,
+    pub is_page: bool,
+
+    pub meta: Option<Scope>,
+    pub html: String,
+}
+
+impl Markdown {
+    pub fn load_absolute<P: AsRef<Path>>(src: P) -> DaisyResult<Markdown> {
+        let src = src.as_ref();
+        let content = fs::read_to_string(src).map_err(|err| {
+            DaisyError::new(format!("Failed to read file: {}", err)).with_file(src)
+        })?;
+        let (meta, body) = front_matter(src, &content)?;
+
+        Ok(Markdown {
+            src: src.to_path_buf(),
+            is_page: false,
+
+            meta,
+            html: render(&content[body..]),
+        })
+    }
+
+    pub fn get_scope(&self, ctx: &mut Context) -> DaisyResult<Scope> {
+        page_scope(ctx, &self.src, self.meta.clone())
+    }
+
+    // the rendered markdown, passed to the layout from the front matter or the config if there is one
+    pub fn render_page(&self, ctx: &mut Context) -> DaisyResult<(Scope, Value)> {
+        let mut scope = self.get_scope(ctx)?;
+        let content = Value::raw(self.html.clone());
+
+        let layout = match self.meta.as_ref().and_then(|meta| meta.get("layout")) {
+            Some(Value::String(layout)) => Some(layout.clone()),
+            Some(layout) => {
+                return Err(DaisyError::new(format!(
+                    "Expected layout to be a string, got {}",
+                    layout.get_type()
+                )))
+            }
+            None => ctx.config.markdown.layout.clone(),
+        };
+        let layout = match layout {
+            Some(layout) => layout,
+            None => return Ok((scope, content)),
+        };
+
+        // layouts are components, the same as `use(layout)(content)` in a page
+        let layout = builtin_use(ctx, &vec![], &vec![Value::String(layout)], &mut scope)?;
+        let content: Expression = Box::new(move |_ctx, _scope| Ok(content.clone()));
+        let value = call_function(ctx, &layout, &vec![content], &mut scope.clone())?;
+        Ok((scope, value))
+    }
+}
+
+pub fn render(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let mut output = String::with_capacity(markdown.len());
+    html::push_html(&mut output, Parser::new_ext(markdown, options));
+    output
+}
+
+// splits off a front matter block fenced by `+++` for toml or `---` for yaml, returns the meta it
+// describes and where the markdown after it starts
+fn front_matter(src: &Path, content: &str) -> DaisyResult<(Option<Scope>, usize)> {
+    let fence = match content.lines().next().map(str::trim_end) {
+        Some("+++") => "+++",
+        Some("---") => "---",
+        _ => return Ok((None, 0)),
+    };
+
+    let start = content.find('\n').map_or(content.len(), |i| i + 1);
+    let mut end = start;
+    for line in content[start..].split_inclusive('\n') {
+        if line.trim_end() == fence {
+            let meta = parse_front_matter(src, &content[start..end], fence, start)?;
+            return Ok((Some(meta), end + line.len()));
+        }
+        end += line.len();
+    }
+
+    Err(DaisyError::new(format!(
+        "Unclosed front matter, expected a closing {}",
+        fence
+    ))
+    .with_location(src, (0, fence.len())))
+}
+
+fn parse_front_matter(src: &Path, matter: &str, fence: &str, offset: usize) -> DaisyResult<Scope> {
+    let value = if fence == "+++" {
+        let table: toml::Table = toml::from_str(matter).map_err(|err| {
+            let error = DaisyError::new(format!("Invalid front matter: {}", err.message()));
+            match err.span() {
+                Some(span) => error.with_location(src, (offset + span.start, offset + span.end)),
+                None => error.with_file(src),
+            }
+        })?;
+        convert::from_toml_table(&table)
+    } else {
+        let value: serde_yaml::Value = serde_yaml::from_str(matter).map_err(|err| {
+            let error = DaisyError::new(format!("Invalid front matter: {}", err));
+            match err.location() {
+                Some(location) => {
+                    let index = offset + location.index();
+                    error.with_location(src, (index, index))
+                }
+                None => error.with_file(src),
+            }
+        })?;
+        match value {
+            serde_yaml::Value::Null => Value::Map(Scope::new()),
+            value => convert::from_yaml(&value),
+        }
+    };
+
+    match value {
+        Value::Map(meta) => Ok(meta),
+        value => Err(DaisyError::new(format!(
+            "Expected front matter to be a map, got {}",
+            value.get_type()
+        ))
+        .with_location(src, (offset, offset))),
+    }
+}
@@ -1,85 +1,175 @@
 use std::{
     cell::RefCell,
-    hash::{DefaultHasher, Hash, Hasher},
-    path::Path,
+    collections::HashSet,
+    path::{Path, PathBuf},
     rc::Rc,
 };
 
 use resource::Resource;
+use uuid::Uuid;
 use walkdir::WalkDir;
+use xxhash_rust::xxh3::xxh3_64;
 
-use crate::context::Context;
+use crate::ast::environment::Scope;
+use crate::context::{Context, Fingerprint};
+use crate::error::{DaisyError, DaisyResult};
 
+pub mod data;
+pub mod dependencies;
 pub mod file;
+pub mod markdown;
 pub mod resource;
 
-pub fn load_dir(ctx: &mut Context) {
+pub fn load_dir(ctx: &mut Context) -> Vec<DaisyError> {
+    let mut errors = vec![];
+
     WalkDir::new(format!(
         "{}/{}",
         ctx.config.paths.workdir, ctx.config.paths.pages
     ))
     .into_iter()
     .filter_map(|entry| entry.ok())
-    .filter(|entry| entry.file_type().is_file() && entry.path().extension() == Some("ds".as_ref()))
+    .filter(|entry| {
+        entry.file_type().is_file()
+            && matches!(
+                entry.path().extension().and_then(|ext| ext.to_str()),
+                Some("ds") | Some("md")
+            )
+    })
     .for_each(|entry| {
         let path = entry.path();
-        let file = get_file(ctx, path.to_str().unwrap().to_string()).unwrap_or_else(|err| {
-            panic!("Failed to load file {}: {}", path.display(), err);
-        });
+        let file = match get_file(ctx, path.to_str().unwrap().to_string()) {
+            Ok(file) => file,
+            Err(err) => {
+                errors.push(err.with_file(path));
+                return;
+            }
+        };
 
         let mut resource = file.borrow_mut();
 
-        if let Resource::File(file) = &mut *resource {
-            file.is_page = true;
-        } else {
-            panic!(
-                "Expected a File resource, got {}",
-                entry.path().to_str().unwrap()
-            );
+        match &mut *resource {
+            Resource::File(file) => file.is_page = true,
+            Resource::Markdown(markdown) => markdown.is_page = true,
+            _ => errors.push(DaisyError::new("Expected a File resource").with_file(path)),
         }
     });
+
+    errors
 }
 
 pub fn get_all(ctx: &mut Context) -> Vec<Rc<RefCell<Resource>>> {
     ctx.resources.iter().cloned().collect()
 }
 
-pub fn get_file(ctx: &mut Context, src: String) -> Result<Rc<RefCell<Resource>>, String> {
+// the meta of every page together with its source. the pages directory is recorded as a dependency
+// so pages listing pages are rebuilt when a page is added, changed or deleted
+pub fn get_pages(ctx: &mut Context) -> DaisyResult<Vec<(PathBuf, Scope)>> {
+    let pages_path = Path::new(&ctx.config.paths.workdir).join(&ctx.config.paths.pages);
+    ctx.dependencies.record(&pages_path);
+
+    let mut pages = vec![];
+    for rc in get_all(ctx) {
+        let (src, scope) = match &*rc.borrow() {
+            Resource::File(file) if file.is_page => (file.src.clone(), file.get_scope(ctx)?),
+            Resource::Markdown(markdown) if markdown.is_page => {
+                (markdown.src.clone(), markdown.get_scope(ctx)?)
+            }
+            _ => continue, // Skip non-page files
+        };
+
+        let meta = scope
+            .get_meta()
+            .cloned()
+            .and_then(|meta| meta.try_into_map())
+            .ok_or_else(|| DaisyError::new("No meta found for page").with_file(&src))?;
+        pages.push((src, meta));
+    }
+    Ok(pages)
+}
+
+// adds content generated during the build as a resource written to `url`, content generated for the
+// same url before is replaced
+pub fn generate(ctx: &mut Context, url: &str, content: String) -> DaisyResult<String> {
+    let output = Resource::get_output_path(ctx, url)?;
+    let output = output.to_str().unwrap().to_string();
+    ctx.resources.retain(
+        |rs| !matches!(&*rs.borrow(), Resource::Generated(existing, _) if *existing == output),
+    );
+    ctx.resources.push(Rc::new(RefCell::new(Resource::Generated(
+        output.clone(),
+        content,
+    ))));
+    Resource::get_url(ctx, &output)
+}
+
+pub fn invalidate(ctx: &mut Context, changed: &HashSet<PathBuf>) {
+    // a deleted directory takes every resource in it along
+    ctx.resources.retain(|rs| {
+        let src = rs.borrow();
+        !changed.iter().any(|path| src.get_src().starts_with(path))
+    });
+    for src in changed {
+        ctx.dependencies.forget(src);
+    }
+
+    let data = ctx.get_data_path();
+    if changed.iter().any(|src| src.starts_with(&data)) {
+        ctx.data = None;
+    }
+}
+
+pub fn get_file(ctx: &mut Context, src: String) -> DaisyResult<Rc<RefCell<Resource>>> {
     let src = Path::new(ctx.config.paths.workdir.as_str()).join(src);
-    if let Some(rs) = ctx.resources.iter().find(|rs| match &*rs.borrow() {
-        Resource::File(file) => file.src == src,
-        Resource::SCSS(src_file, _, _) => src_file == src.to_str().unwrap(),
-        Resource::Other(src_file, _) => src_file == src.to_str().unwrap(),
-    }) {
+    ctx.dependencies.record(&src);
+    if let Some(rs) = ctx.resources.iter().find(|rs| rs.borrow().get_src() == src) {
         Ok(rs.clone())
     } else {
         match src.extension().and_then(|ext| ext.to_str()) {
             Some("ds") => {
-                let file = file::File::load_absolute(ctx, src.to_str().unwrap());
+                let file = file::File::load_absolute(ctx, src.to_str().unwrap())?;
 
                 let rc = Rc::new(RefCell::new(Resource::File(file)));
                 ctx.resources.push(rc.clone());
                 Ok(rc)
             }
+            Some("md") => {
+                let markdown = markdown::Markdown::load_absolute(&src)?;
+
+                let rc = Rc::new(RefCell::new(Resource::Markdown(markdown)));
+                ctx.resources.push(rc.clone());
+                Ok(rc)
+            }
+            _ if data::is_data(&src) => {
+                let value = data::load_absolute(&src)?;
+
+                let rc = Rc::new(RefCell::new(Resource::Data(
+                    src.to_str().unwrap().to_string(),
+                    value,
+                )));
+                ctx.resources.push(rc.clone());
+                Ok(rc)
+            }
             Some("scss") => {
-                let content = std::fs::read_to_string(&src)
-                    .map_err(|_| format!("Failed to read SCSS file: {}", src.display()))?;
-                let css = grass::from_string(content.clone(), &grass::Options::default()).map_err(
-                    |err| format!("Failed to compile SCSS file: {}: {}", src.display(), err),
-                );
+                let content = std::fs::read_to_string(&src).map_err(|err| {
+                    DaisyError::new(format!("Failed to read SCSS file: {}", err)).with_file(&src)
+                })?;
+                let mut options = grass::Options::default();
+                if ctx.config.minify.enabled && ctx.config.minify.css {
+                    options = options.style(grass::OutputStyle::Compressed);
+                }
+                let css = grass::from_string(content.clone(), &options).map_err(|err| {
+                    DaisyError::new(format!("Failed to compile SCSS file: {}", err)).with_file(&src)
+                })?;
 
                 let name = src.file_stem().unwrap().to_str().unwrap();
-                let mut hasher = DefaultHasher::new();
-                content.hash(&mut hasher);
-                let hash = hasher.finish();
-                let path =
-                    Resource::get_output_path(ctx, format!("{}-{}.css", name, hash).as_str())
-                        .unwrap();
+                let path = Resource::get_output_path(ctx, format!("{}.css", name).as_str())?;
+                let path = fingerprint(ctx, path, || Ok(css.clone().into_bytes()))?;
 
                 let rc = Rc::new(RefCell::new(Resource::SCSS(
                     src.to_str().unwrap().to_string(),
                     path.to_str().unwrap().to_string(),
-                    css.unwrap(),
+                    css,
                 )));
                 ctx.resources.push(rc.clone());
                 Ok(rc)
@@ -90,15 +180,19 @@ pub fn get_file(ctx: &mut Context, src: String) -> Result<Rc<RefCell<Resource>>,
                     get_file(ctx, with_ext.to_str().unwrap().to_string())
                 } else {
                     let relative_path =
-                        Resource::get_relative_path_from_root(ctx, src.to_str().unwrap())
-                            .map_err(|err| format!("Failed to get relative path: {}", err))?;
+                        Resource::get_relative_path_from_root(ctx, src.to_str().unwrap())?;
 
-                    let mut output = Resource::get_output_path(ctx, &relative_path)
-                        .map_err(|err| format!("Failed to get output path: {}", err))?;
+                    let mut output = Resource::get_output_path(ctx, &relative_path)?;
 
                     if src.extension().is_none() {
                         output.pop();
                     }
+                    let output = fingerprint(ctx, output, || {
+                        std::fs::read(&src).map_err(|err| {
+                            DaisyError::new(format!("Failed to read asset: {}", err))
+                                .with_file(&src)
+                        })
+                    })?;
 
                     let rc = Rc::new(RefCell::new(Resource::Other(
                         src.to_str().unwrap().to_string(),
@@ -112,3 +206,22 @@ pub fn get_file(ctx: &mut Context, src: String) -> Result<Rc<RefCell<Resource>>,
         }
     }
 }
+
+// adds a fingerprint to the file name of an asset so it can be cached until its content changes
+fn fingerprint<F>(ctx: &Context, path: PathBuf, content: F) -> DaisyResult<PathBuf>
+where
+    F: FnOnce() -> DaisyResult<Vec<u8>>,
+{
+    let fingerprint = match ctx.config.assets.fingerprint() {
+        Fingerprint::None => return Ok(path),
+        Fingerprint::Hash => format!("{:016x}", xxh3_64(&content()?)),
+        Fingerprint::Uuid => Uuid::new_v4().simple().to_string(),
+    };
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, fingerprint, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, fingerprint),
+    };
+    Ok(path.with_file_name(name))
+}
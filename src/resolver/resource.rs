@@ -3,23 +3,40 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use crate::ast::environment::Value;
 use crate::context::Context;
+use crate::error::{DaisyError, DaisyResult};
 
-use super::file::File;
+use super::{file::File, markdown::Markdown};
 
 pub enum Resource {
     File(File),
+    Markdown(Markdown),
     SCSS(String, String, String),
     Other(String, String),
+    Data(String, Value),
+    // content generated while building, like feeds, with the path it is written to
+    Generated(String, String),
 }
 
 impl Resource {
+    pub fn get_src(&self) -> &Path {
+        match self {
+            Resource::File(file) => &file.src,
+            Resource::Markdown(markdown) => &markdown.src,
+            Resource::SCSS(src, _, _) => Path::new(src),
+            Resource::Other(src, _) => Path::new(src),
+            Resource::Data(src, _) => Path::new(src),
+            Resource::Generated(output, _) => Path::new(output),
+        }
+    }
+
     pub fn get_output_path(ctx: &mut Context, src: &str) -> Result<PathBuf, Error> {
         let mut path = Path::new(src);
         path = path.strip_prefix(ctx.get_page_path()).unwrap_or(path);
 
         if let Some(ext) = path.extension() {
-            if ext == "ds" {
+            if ext == "ds" || ext == "md" {
                 let name = path.file_stem().unwrap();
                 let pathbuf = path.parent().unwrap().join(name);
                 path = pathbuf.as_path();
@@ -44,19 +61,31 @@ impl Resource {
         }
     }
 
-    pub fn get_relative_path(ctx: &mut Context, src: &str) -> Result<String, Error> {
+    pub fn get_relative_path(ctx: &mut Context, src: &str) -> DaisyResult<String> {
         if let Some(relative_path) = src.strip_prefix(&ctx.get_output_path()) {
-            return Ok(format!("/{}", relative_path));
+            Ok(format!("/{}", relative_path))
         } else {
-            panic!("Failed to strip output directory from path: {}", src,);
+            Err(DaisyError::new(format!(
+                "Failed to strip output directory from path: {}",
+                src
+            )))
         }
     }
 
-    pub fn get_relative_path_from_root(ctx: &mut Context, src: &str) -> Result<String, Error> {
+    // the url a file in the output directory is served at, including the base path
+    pub fn get_url(ctx: &mut Context, src: &str) -> DaisyResult<String> {
+        let relative_path = Resource::get_relative_path(ctx, src)?;
+        Ok(ctx.get_url(&relative_path))
+    }
+
+    pub fn get_relative_path_from_root(ctx: &mut Context, src: &str) -> DaisyResult<String> {
         if let Some(relative_path) = src.strip_prefix(&ctx.config.paths.workdir) {
-            return Ok(format!("/{}", relative_path));
+            Ok(format!("/{}", relative_path))
         } else {
-            panic!("Failed to strip workdir from path: {}", src);
+            Err(DaisyError::new(format!(
+                "Failed to strip workdir from path: {}",
+                src
+            )))
         }
     }
 }